@@ -0,0 +1,297 @@
+use crate::calendar::clip_session;
+use crate::rounding::Rounding;
+use crate::{ProjectSettings, ProjectSummary, WorkSession};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Currency used when a project has a rate but no explicit currency.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// Whether a session counts as billable: the session override wins,
+/// otherwise the project's flag applies.
+pub fn is_billable(session: &WorkSession, project: Option<&ProjectSettings>) -> bool {
+    session
+        .billable
+        .unwrap_or_else(|| project.is_some_and(|p| p.billable))
+}
+
+/// Effective hourly rate for a session: the session override wins,
+/// otherwise the project's rate applies.
+pub fn hourly_rate(session: &WorkSession, project: Option<&ProjectSettings>) -> Option<f64> {
+    session
+        .hourly_rate
+        .or_else(|| project.and_then(|p| p.hourly_rate))
+}
+
+/// Rounding rule for a project: its own override, or the global default.
+pub fn rounding(project: Option<&ProjectSettings>, default: Rounding) -> Rounding {
+    project.and_then(|p| p.rounding).unwrap_or(default)
+}
+
+pub fn currency(project: Option<&ProjectSettings>) -> String {
+    project
+        .and_then(|p| p.currency.clone())
+        .unwrap_or_else(|| DEFAULT_CURRENCY.to_string())
+}
+
+/// Parse an hourly rate: a finite amount of zero or more.
+pub fn parse_rate(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate >= 0.0 => Ok(rate),
+        _ => Err(format!(
+            "invalid rate '{}': expected an amount of zero or more, like 80 or 72.5",
+            s
+        )),
+    }
+}
+
+/// Money earned for `duration` at `rate` per hour.
+pub fn earnings(duration: Duration, rate: f64) -> f64 {
+    duration.num_seconds() as f64 / 3600.0 * rate
+}
+
+/// Billable time and earnings of a single session (zero if not billable).
+pub fn session_billing(
+    session: &WorkSession,
+    project: Option<&ProjectSettings>,
+) -> (Duration, f64) {
+    if !is_billable(session, project) {
+        return (Duration::zero(), 0.0);
+    }
+    let rate = hourly_rate(session, project).unwrap_or(0.0);
+    (session.duration, earnings(session.duration, rate))
+}
+
+/// Group completed, project-tagged work sessions by project and compute
+/// total time, billable time and earnings. Each project's rounding rule (or
+/// `default_rounding`) is applied per session or to the project's totals,
+/// depending on its scope. Sorted by project name.
+pub fn summarize(
+    sessions: &[WorkSession],
+    settings: &HashMap<String, ProjectSettings>,
+    default_rounding: Rounding,
+) -> Vec<ProjectSummary> {
+    summarize_between(sessions, settings, default_rounding, None, None)
+}
+
+/// Like [`summarize`], counting only the time worked within `[from, to)`.
+/// Per-session rounding is applied to whole sessions before they are
+/// clipped to the range, so a session crossing a boundary is rounded once
+/// and its rounded time shared between the two sides.
+pub fn summarize_between(
+    sessions: &[WorkSession],
+    settings: &HashMap<String, ProjectSettings>,
+    default_rounding: Rounding,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Vec<ProjectSummary> {
+    let mut summaries: HashMap<String, ProjectSummary> = HashMap::new();
+
+    for session in sessions {
+        let Some(project) = session.project() else {
+            continue;
+        };
+        let project_settings = settings.get(project);
+        let rule = rounding(project_settings, default_rounding);
+        let mut rounded_session = session.clone();
+        rounded_session.duration = rule.apply_to_session(session.duration);
+        let (Some(part), Some(rounded_part)) = (
+            clip_session(session, from, to),
+            clip_session(&rounded_session, from, to),
+        ) else {
+            continue;
+        };
+
+        let summary = summaries
+            .entry(project.to_string())
+            .or_insert_with(|| ProjectSummary {
+                name: project.to_string(),
+                total: Duration::zero(),
+                rounded: Duration::zero(),
+                billable: Duration::zero(),
+                earnings: 0.0,
+                currency: currency(project_settings),
+            });
+
+        let (billable, earned) = session_billing(&rounded_part, project_settings);
+        summary.total += part.duration;
+        summary.rounded += rounded_part.duration;
+        summary.billable += billable;
+        summary.earnings += earned;
+    }
+
+    for summary in summaries.values_mut() {
+        let rule = rounding(settings.get(&summary.name), default_rounding);
+        summary.rounded = rule.apply_to_total(summary.rounded);
+
+        // Rounding the billable total scales earnings by the same factor,
+        // which keeps mixed per-session rates proportional.
+        let billable = rule.apply_to_total(summary.billable);
+        if !summary.billable.is_zero() {
+            summary.earnings *=
+                billable.num_seconds() as f64 / summary.billable.num_seconds() as f64;
+        }
+        summary.billable = billable;
+    }
+
+    let mut summaries: Vec<ProjectSummary> = summaries.into_values().collect();
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+    summaries
+}
+
+/// Sum earnings per currency, sorted by currency code.
+pub fn earnings_by_currency(summaries: &[ProjectSummary]) -> Vec<(String, f64)> {
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for summary in summaries.iter().filter(|s| s.earnings > 0.0) {
+        *totals.entry(summary.currency.clone()).or_insert(0.0) += summary.earnings;
+    }
+    let mut totals: Vec<(String, f64)> = totals.into_iter().collect();
+    totals.sort_by(|a, b| a.0.cmp(&b.0));
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rounding::{RoundingMode, RoundingScope};
+    use crate::testing::SessionBuilder;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_summarize_applies_project_rates_and_session_overrides() {
+        let mut settings = HashMap::new();
+        settings.insert(
+            "client".to_string(),
+            ProjectSettings {
+                billable: true,
+                hourly_rate: Some(60.0),
+                currency: Some("EUR".to_string()),
+                rounding: None,
+                budget: None,
+            },
+        );
+
+        let now = Utc::now();
+        let sessions = vec![
+            SessionBuilder::ending(now, Duration::minutes(90))
+                .project("client")
+                .build(),
+            SessionBuilder::ending(now, Duration::minutes(30))
+                .project("client")
+                .billable(false)
+                .build(),
+            SessionBuilder::ending(now, Duration::minutes(60))
+                .project("client")
+                .rate(120.0)
+                .build(),
+            SessionBuilder::ending(now, Duration::minutes(45))
+                .project("internal")
+                .build(),
+        ];
+        let summaries = summarize(&sessions, &settings, Rounding::default());
+
+        assert_eq!(summaries.len(), 2);
+        let client = &summaries[0];
+        assert_eq!(client.name, "client");
+        assert_eq!(client.total, Duration::minutes(180));
+        assert_eq!(client.rounded, client.total);
+        assert_eq!(client.billable, Duration::minutes(150));
+        assert!((client.earnings - 210.0).abs() < 1e-9);
+        assert_eq!(client.currency, "EUR");
+
+        let internal = &summaries[1];
+        assert_eq!(internal.billable, Duration::zero());
+        assert_eq!(internal.currency, DEFAULT_CURRENCY);
+
+        assert_eq!(
+            earnings_by_currency(&summaries),
+            vec![("EUR".to_string(), 210.0)]
+        );
+    }
+
+    #[test]
+    fn test_summarize_rounds_reported_time_but_not_stored_time() {
+        let mut settings = HashMap::new();
+        settings.insert(
+            "client".to_string(),
+            ProjectSettings {
+                billable: true,
+                hourly_rate: Some(60.0),
+                currency: None,
+                rounding: Some(
+                    Rounding::new(RoundingMode::Up, 15).with_scope(RoundingScope::Session),
+                ),
+                budget: None,
+            },
+        );
+        let sessions: Vec<WorkSession> = [
+            ("client", 10),
+            ("client", 20),
+            ("internal", 10),
+            ("internal", 20),
+        ]
+        .into_iter()
+        .map(|(project, minutes)| {
+            SessionBuilder::ending(Utc::now(), Duration::minutes(minutes))
+                .project(project)
+                .build()
+        })
+        .collect();
+
+        let summaries = summarize(&sessions, &settings, Rounding::new(RoundingMode::Up, 6));
+        let client = &summaries[0];
+        assert_eq!(client.total, Duration::minutes(30));
+        assert_eq!(client.rounded, Duration::minutes(45));
+        assert_eq!(client.billable, Duration::minutes(45));
+        assert!((client.earnings - 45.0).abs() < 1e-9);
+
+        let internal = &summaries[1];
+        assert_eq!(internal.rounded, Duration::minutes(30));
+        assert_eq!(sessions[0].duration, Duration::minutes(10));
+    }
+
+    #[test]
+    fn test_session_across_midnight_is_rounded_once() {
+        let rule = Rounding::new(RoundingMode::Up, 15).with_scope(RoundingScope::Session);
+        let midnight = Utc.with_ymd_and_hms(2026, 3, 4, 0, 0, 0).unwrap();
+        let sessions = vec![
+            SessionBuilder::starting(midnight - Duration::minutes(10), Duration::minutes(30))
+                .project("client")
+                .build(),
+        ];
+        let settings = HashMap::new();
+
+        let day = Duration::days(1);
+        let before = summarize_between(
+            &sessions,
+            &settings,
+            rule,
+            Some(midnight - day),
+            Some(midnight),
+        );
+        let after = summarize_between(
+            &sessions,
+            &settings,
+            rule,
+            Some(midnight),
+            Some(midnight + day),
+        );
+        assert_eq!(before[0].total, Duration::minutes(10));
+        assert_eq!(after[0].total, Duration::minutes(20));
+        assert_eq!(before[0].rounded + after[0].rounded, Duration::minutes(30));
+        assert_eq!(
+            summarize(&sessions, &settings, rule)[0].rounded,
+            Duration::minutes(30)
+        );
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("80"), Ok(80.0));
+        assert_eq!(parse_rate("72.5"), Ok(72.5));
+        assert_eq!(parse_rate("0"), Ok(0.0));
+        for bad in ["-5", "inf", "NaN", "abc", ""] {
+            assert!(parse_rate(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+}
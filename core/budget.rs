@@ -0,0 +1,192 @@
+use crate::WorkSession;
+use crate::calendar::{Calendar, clip_session};
+use crate::duration::parse_hours;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Budget usage levels (in percent) that trigger a `TimerEvent::BudgetThreshold`.
+pub const ALERT_THRESHOLDS: [u8; 2] = [80, 100];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    /// The limit applies to all time ever tracked on the project.
+    #[default]
+    Total,
+    /// The limit resets at the start of every calendar week.
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    pub limit: Duration,
+    #[serde(default)]
+    pub period: BudgetPeriod,
+}
+
+/// Parse a budget limit given in hours, e.g. "10" or "2.5".
+pub fn parse_budget_hours(s: &str) -> Result<Duration, String> {
+    match parse_hours(s) {
+        Ok(limit) if limit > Duration::zero() => Ok(limit),
+        _ => Err(format!(
+            "invalid budget '{}': expected a positive number of hours, like 10 or 2.5",
+            s
+        )),
+    }
+}
+
+/// How much of a project's budget has been consumed in the current period.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    pub consumed: Duration,
+}
+
+impl BudgetStatus {
+    /// Time left before the budget is exhausted (zero once exceeded).
+    pub fn remaining(&self) -> Duration {
+        (self.budget.limit - self.consumed).max(Duration::zero())
+    }
+
+    pub fn percent(&self) -> f64 {
+        percent(self.consumed, self.budget.limit)
+    }
+}
+
+fn percent(consumed: Duration, limit: Duration) -> f64 {
+    if limit.num_seconds() <= 0 {
+        return 100.0;
+    }
+    consumed.num_seconds() as f64 / limit.num_seconds() as f64 * 100.0
+}
+
+/// Start of the budget period containing `now`, or `None` for all-time budgets.
+pub fn period_start(
+    period: BudgetPeriod,
+    now: DateTime<Utc>,
+    calendar: &Calendar,
+) -> Option<DateTime<Utc>> {
+    match period {
+        BudgetPeriod::Total => None,
+        BudgetPeriod::Weekly => Some(calendar.week_start_of(now)),
+    }
+}
+
+/// Time tracked on `project` by completed sessions within the budget period
+/// that contains `now`; sessions started before the period only count for
+/// the part worked since it began.
+pub fn consumed(
+    sessions: &[WorkSession],
+    project: &str,
+    period: BudgetPeriod,
+    now: DateTime<Utc>,
+    calendar: &Calendar,
+) -> Duration {
+    let since = period_start(period, now, calendar);
+    sessions
+        .iter()
+        .filter(|s| s.project() == Some(project))
+        .filter_map(|s| clip_session(s, since, None))
+        .map(|s| s.duration)
+        .sum()
+}
+
+/// Thresholds passed while usage grew from `before` to `after`.
+pub fn crossed_thresholds(budget: &Budget, before: Duration, after: Duration) -> Vec<u8> {
+    let (before, after) = (percent(before, budget.limit), percent(after, budget.limit));
+    ALERT_THRESHOLDS
+        .iter()
+        .copied()
+        .filter(|&t| before < t as f64 && after >= t as f64)
+        .collect()
+}
+
+impl FromStr for BudgetPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "total" => Ok(Self::Total),
+            "weekly" | "week" => Ok(Self::Weekly),
+            other => Err(format!(
+                "unknown budget period '{}' (expected total or weekly)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Total => "total",
+            Self::Weekly => "weekly",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SessionBuilder;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_weekly_consumption_and_thresholds() {
+        let calendar = Calendar::utc();
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2026, 3, 4, 12, 0, 0).unwrap();
+        let sessions: Vec<WorkSession> = [(1, 5), (2, 3), (3, 4)]
+            .into_iter()
+            .map(|(day, hours)| {
+                let end = Utc.with_ymd_and_hms(2026, 3, day, 18, 0, 0).unwrap();
+                SessionBuilder::ending(end, Duration::hours(hours))
+                    .project("api")
+                    .build()
+            })
+            .collect();
+
+        assert_eq!(
+            consumed(&sessions, "api", BudgetPeriod::Weekly, now, &calendar),
+            Duration::hours(7)
+        );
+        assert_eq!(
+            consumed(&sessions, "api", BudgetPeriod::Total, now, &calendar),
+            Duration::hours(12)
+        );
+
+        let budget = Budget {
+            limit: Duration::hours(10),
+            period: BudgetPeriod::Weekly,
+        };
+        assert_eq!(
+            crossed_thresholds(&budget, Duration::hours(7), Duration::hours(9)),
+            vec![80]
+        );
+        assert_eq!(
+            crossed_thresholds(&budget, Duration::hours(7), Duration::hours(11)),
+            vec![80, 100]
+        );
+        assert!(crossed_thresholds(&budget, Duration::hours(9), Duration::hours(9)).is_empty());
+
+        let status = BudgetStatus {
+            budget,
+            consumed: Duration::hours(11),
+        };
+        assert_eq!(status.remaining(), Duration::zero());
+    }
+
+    #[test]
+    fn test_parse_budget_hours() {
+        assert_eq!(parse_budget_hours("2.5"), Ok(Duration::minutes(150)));
+        for bad in ["0", "-3", "NaN", "inf", "1e20"] {
+            assert!(
+                parse_budget_hours(bad).is_err(),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+}
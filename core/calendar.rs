@@ -0,0 +1,311 @@
+use crate::{PersistenceError, WorkSession};
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// How tracked time is split into days and weeks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// IANA time zone name such as "Europe/Madrid"; the system time zone is
+    /// used when unset.
+    pub timezone: Option<String>,
+    /// Local time at which a new day begins, e.g. 04:00 for night owls.
+    pub day_starts_at: NaiveTime,
+    pub week_starts_on: Weekday,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            timezone: None,
+            day_starts_at: NaiveTime::MIN,
+            week_starts_on: Weekday::Mon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    System,
+    Named(Tz),
+}
+
+/// Maps instants to the user's days and weeks, honouring the configured
+/// time zone, day start offset and first day of the week. Every day/week
+/// grouping in the core goes through this type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calendar {
+    zone: Zone,
+    day_offset: Duration,
+    week_start: Weekday,
+}
+
+impl Default for Calendar {
+    /// The system time zone, days starting at midnight and weeks on Monday.
+    fn default() -> Self {
+        Self {
+            zone: Zone::System,
+            day_offset: Duration::zero(),
+            week_start: Weekday::Mon,
+        }
+    }
+}
+
+impl Calendar {
+    pub fn from_config(config: &CalendarConfig) -> Result<Self, PersistenceError> {
+        let zone =
+            match &config.timezone {
+                Some(name) => Zone::Named(name.parse::<Tz>().map_err(|_| {
+                    PersistenceError::Config(format!("unknown time zone '{}'", name))
+                })?),
+                None => Zone::System,
+            };
+
+        Ok(Self {
+            zone,
+            day_offset: config.day_starts_at - NaiveTime::MIN,
+            week_start: config.week_starts_on,
+        })
+    }
+
+    /// A calendar in UTC with midnight day boundaries and Monday weeks.
+    pub fn utc() -> Self {
+        Self {
+            zone: Zone::Named(Tz::UTC),
+            ..Self::default()
+        }
+    }
+
+    pub fn week_start(&self) -> Weekday {
+        self.week_start
+    }
+
+    /// Wall-clock time of `instant` in the configured time zone.
+    pub fn local(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        match self.zone {
+            Zone::System => Local.from_utc_datetime(&instant.naive_utc()).naive_local(),
+            Zone::Named(tz) => tz.from_utc_datetime(&instant.naive_utc()).naive_local(),
+        }
+    }
+
+    /// The instant a wall-clock time occurs. Ambiguous times (DST fall-back)
+    /// resolve to the earlier instant; skipped times (DST spring-forward)
+    /// resolve to the first valid time after the gap.
+    pub fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        let mut candidate = local;
+        // DST gaps are at most a couple of hours; step past them.
+        for _ in 0..4 {
+            let resolved = match self.zone {
+                Zone::System => Local
+                    .from_local_datetime(&candidate)
+                    .earliest()
+                    .map(|t| t.with_timezone(&Utc)),
+                Zone::Named(tz) => tz
+                    .from_local_datetime(&candidate)
+                    .earliest()
+                    .map(|t| t.with_timezone(&Utc)),
+            };
+            if let Some(instant) = resolved {
+                return instant;
+            }
+            candidate += Duration::hours(1);
+        }
+        local.and_utc()
+    }
+
+    /// The day an instant belongs to.
+    pub fn day_of(&self, instant: DateTime<Utc>) -> NaiveDate {
+        (self.local(instant) - self.day_offset).date()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.day_of(Utc::now())
+    }
+
+    /// The instant `day` begins.
+    pub fn day_start(&self, day: NaiveDate) -> DateTime<Utc> {
+        self.to_utc(day.and_time(NaiveTime::MIN) + self.day_offset)
+    }
+
+    /// `[start, end)` of `day`.
+    pub fn day_range(&self, day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        (self.day_start(day), self.day_start(day + Duration::days(1)))
+    }
+
+    /// First day of the week containing `day`.
+    pub fn week_of(&self, day: NaiveDate) -> NaiveDate {
+        let offset =
+            (day.weekday().num_days_from_monday() + 7 - self.week_start.num_days_from_monday()) % 7;
+        day - Duration::days(offset as i64)
+    }
+
+    /// The instant the week containing `instant` began.
+    pub fn week_start_of(&self, instant: DateTime<Utc>) -> DateTime<Utc> {
+        self.day_start(self.week_of(self.day_of(instant)))
+    }
+
+    /// Split a completed session into the parts falling on each day it
+    /// overlaps, oldest first. See [`clip_session`] for how time is shared;
+    /// round a session before splitting it, not each part.
+    pub fn split_by_day(&self, session: &WorkSession) -> Vec<(NaiveDate, WorkSession)> {
+        let Some(end) = session.end_time else {
+            return Vec::new();
+        };
+        let first = self.day_of(session.start_time);
+        let last = self.day_of(end).max(first);
+
+        first
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .filter_map(|day| {
+                let (from, to) = self.day_range(day);
+                clip_session(session, Some(from), Some(to)).map(|part| (day, part))
+            })
+            .collect()
+    }
+}
+
+/// Time of `session` worked before `instant`. Paused time isn't recorded
+/// with a timestamp, so it is assumed to be spread evenly over the session.
+fn worked_before(session: &WorkSession, end: DateTime<Utc>, instant: DateTime<Utc>) -> i64 {
+    let total = session.duration.num_milliseconds();
+    let span = (end - session.start_time).num_milliseconds();
+    if span <= 0 {
+        return if instant > end { total } else { 0 };
+    }
+    let elapsed = (instant - session.start_time)
+        .num_milliseconds()
+        .clamp(0, span);
+    (total as i128 * elapsed as i128 / span as i128) as i64
+}
+
+/// The part of a completed session that falls within `[from, to)` (either
+/// bound may be open), with its start/end clamped to the range and its
+/// duration the matching share of the time worked. `None` if the session
+/// is still running or lies outside the range.
+///
+/// Clipping a session at adjacent ranges yields durations that add up
+/// exactly to the original.
+pub fn clip_session(
+    session: &WorkSession,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Option<WorkSession> {
+    let end = session.end_time?;
+    let start = from.map_or(session.start_time, |from| session.start_time.max(from));
+    let clipped_end = to.map_or(end, |to| end.min(to));
+    let instantaneous = session.start_time >= end;
+    if from.is_some_and(|from| end < from || (!instantaneous && end == from))
+        || to.is_some_and(|to| session.start_time >= to)
+    {
+        return None;
+    }
+
+    let worked_from = from.map_or(0, |from| worked_before(session, end, from));
+    let worked_to = to.map_or(session.duration.num_milliseconds(), |to| {
+        worked_before(session, end, to)
+    });
+    let duration = Duration::milliseconds(worked_to - worked_from);
+
+    Some(WorkSession {
+        start_time: start,
+        end_time: Some(clipped_end),
+        duration,
+        paused_duration: ((clipped_end - start) - duration).max(Duration::zero()),
+        ..session.clone()
+    })
+}
+
+/// Parse a time of day given as "HH:MM" or "HH:MM:SS".
+pub fn parse_time_of_day(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .map_err(|_| format!("invalid time of day '{}' (expected HH:MM)", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SessionBuilder;
+
+    #[test]
+    fn test_day_boundaries_follow_timezone_and_day_start() {
+        let calendar = Calendar::from_config(&CalendarConfig {
+            timezone: Some("America/New_York".to_string()),
+            day_starts_at: NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+            week_starts_on: Weekday::Sun,
+        })
+        .unwrap();
+
+        // 2026-03-04 02:30 UTC is 21:30 on the 3rd in New York.
+        let evening = Utc.with_ymd_and_hms(2026, 3, 4, 2, 30, 0).unwrap();
+        assert_eq!(
+            calendar.day_of(evening),
+            NaiveDate::from_ymd_opt(2026, 3, 3).unwrap()
+        );
+
+        // 03:00 local on the 4th is still the 3rd for a 04:00 day start.
+        let late_night = Utc.with_ymd_and_hms(2026, 3, 4, 8, 0, 0).unwrap();
+        assert_eq!(
+            calendar.day_of(late_night),
+            NaiveDate::from_ymd_opt(2026, 3, 3).unwrap()
+        );
+
+        let day = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        assert_eq!(
+            calendar.day_start(day),
+            Utc.with_ymd_and_hms(2026, 3, 4, 9, 0, 0).unwrap()
+        );
+        // Wednesday 4th belongs to the week starting Sunday 1st.
+        assert_eq!(
+            calendar.week_of(day),
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+        );
+
+        assert!(
+            Calendar::from_config(&CalendarConfig {
+                timezone: Some("Mars/Olympus".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_overnight_session_is_split_between_days() {
+        let calendar = Calendar::utc();
+        let start = Utc.with_ymd_and_hms(2026, 3, 3, 23, 0, 0).unwrap();
+        let session = SessionBuilder::starting(start, Duration::hours(2))
+            .paused(Duration::minutes(30))
+            .project("api")
+            .build();
+
+        let parts = calendar.split_by_day(&session);
+        let days: Vec<(NaiveDate, Duration)> = parts
+            .iter()
+            .map(|(day, part)| (*day, part.duration))
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                (
+                    NaiveDate::from_ymd_opt(2026, 3, 3).unwrap(),
+                    Duration::minutes(45)
+                ),
+                (
+                    NaiveDate::from_ymd_opt(2026, 3, 4).unwrap(),
+                    Duration::minutes(45)
+                ),
+            ]
+        );
+        assert_eq!(parts[1].1.start_time, calendar.day_start(parts[1].0));
+
+        let after_midnight = clip_session(&session, Some(parts[1].1.start_time), None).unwrap();
+        assert_eq!(after_midnight.duration, Duration::minutes(45));
+        assert!(clip_session(&session, session.end_time, None).is_none());
+    }
+}
@@ -0,0 +1,766 @@
+use crate::calendar::{self, Calendar};
+use crate::duration::{format_duration, parse_duration};
+use crate::hooks::Hook;
+use crate::{PersistenceError, TimerConfig};
+use chrono::{Duration, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A Pomodoro rhythm: the `[timer]` lengths and flags, saved under a name
+/// such as "deep" for 52/17 deep work.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimerPreset {
+    pub work: Duration,
+    pub short_break: Duration,
+    pub long_break: Duration,
+    pub long_break_interval: u32,
+    pub auto_start_breaks: bool,
+    pub auto_start_work: bool,
+}
+
+impl TimerPreset {
+    fn new(work: i64, short_break: i64, long_break: i64, long_break_interval: u32) -> Self {
+        Self {
+            work: Duration::minutes(work),
+            short_break: Duration::minutes(short_break),
+            long_break: Duration::minutes(long_break),
+            long_break_interval,
+            auto_start_breaks: false,
+            auto_start_work: false,
+        }
+    }
+
+    /// Value of a `[timer]` field such as "work".
+    fn get(&self, field: &str) -> Option<String> {
+        Some(match field {
+            "work" => format_duration(self.work),
+            "short_break" => format_duration(self.short_break),
+            "long_break" => format_duration(self.long_break),
+            "long_break_interval" => self.long_break_interval.to_string(),
+            "auto_start_breaks" => self.auto_start_breaks.to_string(),
+            "auto_start_work" => self.auto_start_work.to_string(),
+            _ => return None,
+        })
+    }
+
+    /// Change a `[timer]` field; `key` is the full setting name, for errors.
+    fn set(&mut self, key: &str, field: &str, value: &str) -> Result<(), PersistenceError> {
+        let err = |message| invalid(key, message);
+        match field {
+            "work" => self.work = parse_duration(value).map_err(err)?,
+            "short_break" => self.short_break = parse_duration(value).map_err(err)?,
+            "long_break" => self.long_break = parse_duration(value).map_err(err)?,
+            "long_break_interval" => {
+                self.long_break_interval = match value.parse::<u32>() {
+                    Ok(interval) if interval > 0 => interval,
+                    _ => return Err(err(format!("'{}' is not a positive number", value))),
+                }
+            }
+            "auto_start_breaks" => self.auto_start_breaks = parse_bool(value).map_err(err)?,
+            "auto_start_work" => self.auto_start_work = parse_bool(value).map_err(err)?,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+const PRESET_FIELDS: &[&str] = &[
+    "work",
+    "short_break",
+    "long_break",
+    "long_break_interval",
+    "auto_start_breaks",
+    "auto_start_work",
+];
+
+/// Presets in a new config: the classic 25/5 rhythm and 52/17 deep work.
+pub fn default_presets() -> BTreeMap<String, TimerPreset> {
+    BTreeMap::from([
+        ("classic".to_string(), TimerPreset::new(25, 5, 15, 4)),
+        ("deep".to_string(), TimerPreset::new(52, 17, 30, 3)),
+    ])
+}
+
+fn is_valid_preset_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Terminal output preferences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Colored output; `NO_COLOR` is honoured when this is on.
+    pub color: bool,
+    /// Width of the bars in `chart`, in characters.
+    pub chart_width: usize,
+    /// Line printed by `status --short`, with {icon}, {clock}, {elapsed},
+    /// {remaining}, {timer}, {state}, {project} and {description}.
+    pub status_template: String,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            color: true,
+            chart_width: 40,
+            status_template: "{icon} {clock} {project}".to_string(),
+        }
+    }
+}
+
+/// A setting that can be read and changed by name, e.g. `timer.work`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigKey {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Every setting `TimerConfig::get`/`set` understand, in display order.
+pub const KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        name: "timer.work",
+        description: "Length of a Pomodoro work session",
+    },
+    ConfigKey {
+        name: "timer.short_break",
+        description: "Length of a short break",
+    },
+    ConfigKey {
+        name: "timer.long_break",
+        description: "Length of a long break",
+    },
+    ConfigKey {
+        name: "timer.long_break_interval",
+        description: "Pomodoros before a long break",
+    },
+    ConfigKey {
+        name: "timer.auto_start_breaks",
+        description: "Start a break when a Pomodoro ends",
+    },
+    ConfigKey {
+        name: "timer.auto_start_work",
+        description: "Start a Pomodoro when a break ends",
+    },
+    ConfigKey {
+        name: "calendar.timezone",
+        description: "IANA time zone, or \"system\"",
+    },
+    ConfigKey {
+        name: "calendar.day_starts_at",
+        description: "Local time a new day begins (HH:MM)",
+    },
+    ConfigKey {
+        name: "calendar.week_starts_on",
+        description: "First day of the week",
+    },
+    ConfigKey {
+        name: "goals.daily_focus",
+        description: "Focused time per day (\"off\" to disable)",
+    },
+    ConfigKey {
+        name: "goals.daily_pomodoros",
+        description: "Completed Pomodoros per day (\"off\" to disable)",
+    },
+    ConfigKey {
+        name: "goals.weekly_focus",
+        description: "Focused time per week (\"off\" to disable)",
+    },
+    ConfigKey {
+        name: "goals.weekdays_only",
+        description: "Only apply daily goals on weekdays",
+    },
+    ConfigKey {
+        name: "reports.days",
+        description: "Days covered by `report` and `stats` by default",
+    },
+    ConfigKey {
+        name: "reports.rounding",
+        description: "Round reported time up, down or to the nearest step",
+    },
+    ConfigKey {
+        name: "reports.rounding_increment",
+        description: "Rounding step in minutes (0 disables rounding)",
+    },
+    ConfigKey {
+        name: "reports.rounding_scope",
+        description: "Round each session or only totals",
+    },
+    ConfigKey {
+        name: "continue.resume_last",
+        description: "Extend the last session if it ended at most this long ago (\"off\" to disable)",
+    },
+    ConfigKey {
+        name: "display.color",
+        description: "Colored output",
+    },
+    ConfigKey {
+        name: "display.chart_width",
+        description: "Width of chart bars, in characters",
+    },
+    ConfigKey {
+        name: "display.status_template",
+        description: "Line printed by `status --short`",
+    },
+    ConfigKey {
+        name: "notifications.pomodoro",
+        description: "Notify when a Pomodoro is complete",
+    },
+    ConfigKey {
+        name: "notifications.breaks",
+        description: "Notify when a break is over",
+    },
+    ConfigKey {
+        name: "notifications.countdown",
+        description: "Notify when a countdown reaches zero",
+    },
+    ConfigKey {
+        name: "notifications.budget",
+        description: "Notify when a project passes a budget alert level",
+    },
+    ConfigKey {
+        name: "notifications.bell",
+        description: "Ring the terminal bell",
+    },
+    ConfigKey {
+        name: "notifications.desktop",
+        description: "Show desktop notifications, if built with them",
+    },
+    ConfigKey {
+        name: "hooks.on_start",
+        description: "Run when a Pomodoro, stopwatch or countdown starts",
+    },
+    ConfigKey {
+        name: "hooks.on_stop",
+        description: "Run when a timer is stopped and its session saved",
+    },
+    ConfigKey {
+        name: "hooks.on_pause",
+        description: "Run when a timer is paused",
+    },
+    ConfigKey {
+        name: "hooks.on_resume",
+        description: "Run when a paused timer resumes",
+    },
+    ConfigKey {
+        name: "hooks.on_complete",
+        description: "Run when a Pomodoro phase or countdown reaches its end",
+    },
+    ConfigKey {
+        name: "hooks.on_break_start",
+        description: "Run instead of on_start when a break starts",
+    },
+    ConfigKey {
+        name: "hooks.timeout",
+        description: "Time a hook may run before it is killed",
+    },
+];
+
+/// Config file sections, in file order, with the comment written above each.
+const SECTIONS: &[(&str, &str)] = &[
+    ("timer", "Pomodoro and break lengths"),
+    ("calendar", "How time is split into days and weeks"),
+    (
+        "goals",
+        "Daily and weekly targets (\"off\" disables a goal)",
+    ),
+    ("reports", "Reports and invoices"),
+    ("continue", "Picking up earlier sessions with `continue`"),
+    ("display", "Terminal output"),
+    (
+        "notifications",
+        "When and how timer completions are announced",
+    ),
+    (
+        "hooks",
+        "Shell commands run on timer events (\"\" runs nothing). Session details\n\
+         # are passed as NETUPI_* environment variables and as JSON on stdin",
+    ),
+];
+
+fn unknown_key(key: &str) -> PersistenceError {
+    PersistenceError::Config(format!(
+        "unknown setting '{}' (see 'config list' for valid names)",
+        key
+    ))
+}
+
+fn invalid(key: &str, message: String) -> PersistenceError {
+    PersistenceError::Config(format!("invalid value for {}: {}", key, message))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" => Ok(true),
+        "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("'{}' is not true or false", value)),
+    }
+}
+
+/// A positive duration, or `None` for "off".
+fn parse_optional_duration(value: &str) -> Result<Option<Duration>, String> {
+    match value.to_lowercase().as_str() {
+        "off" | "none" | "0" => Ok(None),
+        _ => parse_duration(value).map(Some),
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "off".to_string(), |v| v.to_string())
+}
+
+impl TimerConfig {
+    /// The default rhythm, from the `[timer]` settings.
+    pub fn timer_preset(&self) -> TimerPreset {
+        TimerPreset {
+            work: self.pomodoro_work_duration,
+            short_break: self.pomodoro_short_break_duration,
+            long_break: self.pomodoro_long_break_duration,
+            long_break_interval: self.long_break_interval,
+            auto_start_breaks: self.auto_start_breaks,
+            auto_start_work: self.auto_start_work,
+        }
+    }
+
+    fn set_timer_preset(&mut self, preset: TimerPreset) {
+        self.pomodoro_work_duration = preset.work;
+        self.pomodoro_short_break_duration = preset.short_break;
+        self.pomodoro_long_break_duration = preset.long_break;
+        self.long_break_interval = preset.long_break_interval;
+        self.auto_start_breaks = preset.auto_start_breaks;
+        self.auto_start_work = preset.auto_start_work;
+    }
+
+    pub fn preset(&self, name: &str) -> Result<&TimerPreset, PersistenceError> {
+        self.presets.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.presets.keys().map(String::as_str).collect();
+            PersistenceError::Config(format!(
+                "unknown preset '{}' (available: {})",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            ))
+        })
+    }
+
+    /// Make a preset the default rhythm by copying it into `[timer]`.
+    pub fn use_preset(&mut self, name: &str) -> Result<(), PersistenceError> {
+        let preset = self.preset(name)?.clone();
+        self.set_timer_preset(preset);
+        Ok(())
+    }
+
+    /// The first preset matching the `[timer]` settings, if any.
+    pub fn active_preset(&self) -> Option<&str> {
+        let current = self.timer_preset();
+        self.presets
+            .iter()
+            .find(|(_, preset)| **preset == current)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Save the `[timer]` settings as a preset, replacing any of that name.
+    pub fn save_preset(&mut self, name: &str) -> Result<(), PersistenceError> {
+        if !is_valid_preset_name(name) {
+            return Err(PersistenceError::Config(format!(
+                "invalid preset name '{}' (use letters, digits, '-' and '_')",
+                name
+            )));
+        }
+        self.presets.insert(name.to_string(), self.timer_preset());
+        Ok(())
+    }
+
+    pub fn remove_preset(&mut self, name: &str) -> Result<(), PersistenceError> {
+        self.preset(name)?;
+        self.presets.remove(name);
+        Ok(())
+    }
+
+    /// The current value of a setting, formatted the way `set` accepts it.
+    pub fn get(&self, key: &str) -> Result<String, PersistenceError> {
+        if let Some(field) = key.strip_prefix("timer.") {
+            return self
+                .timer_preset()
+                .get(field)
+                .ok_or_else(|| unknown_key(key));
+        }
+        if let Some((name, field)) = key
+            .strip_prefix("presets.")
+            .and_then(|rest| rest.split_once('.'))
+        {
+            return self
+                .presets
+                .get(name)
+                .and_then(|preset| preset.get(field))
+                .ok_or_else(|| unknown_key(key));
+        }
+
+        let value = match key {
+            "calendar.timezone" => self
+                .calendar
+                .timezone
+                .clone()
+                .unwrap_or_else(|| "system".to_string()),
+            "calendar.day_starts_at" => self.calendar.day_starts_at.format("%H:%M").to_string(),
+            "calendar.week_starts_on" => self.calendar.week_starts_on.to_string(),
+            "goals.daily_focus" => optional(self.goals.daily_focus.map(format_duration)),
+            "goals.daily_pomodoros" => optional(self.goals.daily_pomodoros),
+            "goals.weekly_focus" => optional(self.goals.weekly_focus.map(format_duration)),
+            "goals.weekdays_only" => self.goals.weekdays_only.to_string(),
+            "reports.days" => self.report_days.to_string(),
+            "reports.rounding" => self.rounding.mode.to_string(),
+            "reports.rounding_increment" => self.rounding.increment_minutes.to_string(),
+            "reports.rounding_scope" => self.rounding.scope.to_string(),
+            "continue.resume_last" => optional(self.resume_last.map(format_duration)),
+            "display.color" => self.display.color.to_string(),
+            "display.chart_width" => self.display.chart_width.to_string(),
+            "display.status_template" => self.display.status_template.clone(),
+            "notifications.pomodoro" => self.notifications.pomodoro.to_string(),
+            "notifications.breaks" => self.notifications.breaks.to_string(),
+            "notifications.countdown" => self.notifications.countdown.to_string(),
+            "notifications.budget" => self.notifications.budget.to_string(),
+            "notifications.bell" => self.notifications.bell.to_string(),
+            "notifications.desktop" => self.notifications.desktop.to_string(),
+            "hooks.timeout" => format_duration(self.hooks.timeout),
+            _ => match key.strip_prefix("hooks.").and_then(Hook::from_name) {
+                Some(hook) => self.hooks.command(hook).unwrap_or_default().to_string(),
+                None => return Err(unknown_key(key)),
+            },
+        };
+        Ok(value)
+    }
+
+    /// Change a setting from its text form, validating the value. The
+    /// config is left untouched on error.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), PersistenceError> {
+        let value = value.trim();
+        let err = |message| invalid(key, message);
+        if let Some(field) = key.strip_prefix("timer.") {
+            let mut preset = self.timer_preset();
+            preset.set(key, field, value)?;
+            self.set_timer_preset(preset);
+            return Ok(());
+        }
+        if let Some((name, field)) = key
+            .strip_prefix("presets.")
+            .and_then(|rest| rest.split_once('.'))
+        {
+            // Setting a field of a new preset creates it from the defaults.
+            if !is_valid_preset_name(name) {
+                return Err(unknown_key(key));
+            }
+            let mut preset = self
+                .presets
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| TimerConfig::default().timer_preset());
+            preset.set(key, field, value)?;
+            self.presets.insert(name.to_string(), preset);
+            return Ok(());
+        }
+
+        match key {
+            "calendar.timezone" => {
+                let mut calendar = self.calendar.clone();
+                calendar.timezone = (value != "system").then(|| value.to_string());
+                Calendar::from_config(&calendar)?;
+                self.calendar = calendar;
+            }
+            "calendar.day_starts_at" => {
+                self.calendar.day_starts_at = calendar::parse_time_of_day(value).map_err(err)?
+            }
+            "calendar.week_starts_on" => {
+                self.calendar.week_starts_on = value
+                    .parse::<Weekday>()
+                    .map_err(|_| err(format!("'{}' is not a day of the week", value)))?
+            }
+            "goals.daily_focus" => {
+                self.goals.daily_focus = parse_optional_duration(value).map_err(err)?
+            }
+            "goals.daily_pomodoros" => {
+                self.goals.daily_pomodoros = match value.to_lowercase().as_str() {
+                    "off" | "none" | "0" => None,
+                    _ => Some(
+                        value
+                            .parse()
+                            .map_err(|_| err(format!("'{}' is not a number", value)))?,
+                    ),
+                }
+            }
+            "goals.weekly_focus" => {
+                self.goals.weekly_focus = parse_optional_duration(value).map_err(err)?
+            }
+            "goals.weekdays_only" => self.goals.weekdays_only = parse_bool(value).map_err(err)?,
+            "reports.days" => {
+                self.report_days = match value.parse::<u32>() {
+                    Ok(days) if days > 0 => days,
+                    _ => return Err(err(format!("'{}' is not a positive number", value))),
+                }
+            }
+            "reports.rounding" => self.rounding.mode = value.parse().map_err(err)?,
+            "reports.rounding_increment" => {
+                self.rounding.increment_minutes = value
+                    .parse()
+                    .map_err(|_| err(format!("'{}' is not a number of minutes", value)))?
+            }
+            "reports.rounding_scope" => self.rounding.scope = value.parse().map_err(err)?,
+            "continue.resume_last" => {
+                self.resume_last = parse_optional_duration(value).map_err(err)?
+            }
+            "display.color" => self.display.color = parse_bool(value).map_err(err)?,
+            "display.chart_width" => {
+                self.display.chart_width = match value.parse::<usize>() {
+                    Ok(width) if (10..=200).contains(&width) => width,
+                    _ => return Err(err(format!("'{}' is not between 10 and 200", value))),
+                }
+            }
+            "display.status_template" => self.display.status_template = value.to_string(),
+            "notifications.pomodoro" => {
+                self.notifications.pomodoro = parse_bool(value).map_err(err)?
+            }
+            "notifications.breaks" => self.notifications.breaks = parse_bool(value).map_err(err)?,
+            "notifications.countdown" => {
+                self.notifications.countdown = parse_bool(value).map_err(err)?
+            }
+            "notifications.budget" => self.notifications.budget = parse_bool(value).map_err(err)?,
+            "notifications.bell" => self.notifications.bell = parse_bool(value).map_err(err)?,
+            "notifications.desktop" => {
+                self.notifications.desktop = parse_bool(value).map_err(err)?
+            }
+            "hooks.timeout" => self.hooks.timeout = parse_duration(value).map_err(err)?,
+            _ => match key.strip_prefix("hooks.").and_then(Hook::from_name) {
+                Some(hook) => self
+                    .hooks
+                    .set_command(hook, (!value.is_empty()).then(|| value.to_string())),
+                None => return Err(unknown_key(key)),
+            },
+        }
+        Ok(())
+    }
+
+    /// Restore a setting to its default value.
+    pub fn reset(&mut self, key: &str) -> Result<(), PersistenceError> {
+        let default = TimerConfig::default().get(key)?;
+        self.set(key, &default)
+    }
+
+    /// Every setting with its current value, in [`KEYS`] order.
+    pub fn entries(&self) -> Vec<(ConfigKey, String)> {
+        KEYS.iter()
+            .filter_map(|key| self.get(key.name).ok().map(|value| (*key, value)))
+            .collect()
+    }
+
+    /// Render the config file: one TOML section per key prefix, with every
+    /// setting commented.
+    pub fn to_toml(&self) -> String {
+        let mut text = String::from(
+            "# Netupi23 settings. Durations are written like \"25m\", \"1h30m\" or \"90s\".\n\
+             # Edit this file or use `netupi config set <key> <value>`.\n",
+        );
+        for (section, comment) in SECTIONS {
+            text.push_str(&format!("\n# {}\n[{}]\n", comment, section));
+            for (key, value) in self.entries() {
+                let Some(name) = key
+                    .name
+                    .strip_prefix(*section)
+                    .and_then(|name| name.strip_prefix('.'))
+                else {
+                    continue;
+                };
+                // Commands are text even when they look like a number.
+                let value = if *section == "hooks" && Hook::from_name(name).is_some() {
+                    toml::Value::String(value).to_string()
+                } else {
+                    toml_value(&value)
+                };
+                text.push_str(&format!("# {}\n{} = {}\n", key.description, name, value));
+            }
+
+            if *section == "timer" && !self.presets.is_empty() {
+                text.push_str(
+                    "\n# Named rhythms with the same fields as [timer], for\n\
+                     # `pomodoro --preset <name>`; `preset <name>` makes one the default.\n",
+                );
+                for (i, (name, preset)) in self.presets.iter().enumerate() {
+                    if i > 0 {
+                        text.push('\n');
+                    }
+                    text.push_str(&format!("[presets.{}]\n", name));
+                    for field in PRESET_FIELDS {
+                        if let Some(value) = preset.get(field) {
+                            text.push_str(&format!("{} = {}\n", field, toml_value(&value)));
+                        }
+                    }
+                }
+            }
+        }
+        text
+    }
+
+    /// Parse a config file written by [`TimerConfig::to_toml`] (or by hand).
+    /// Missing settings keep their defaults; unknown ones are an error so
+    /// typos don't go unnoticed.
+    pub fn from_toml(text: &str) -> Result<Self, PersistenceError> {
+        let table: toml::Table = text
+            .parse()
+            .map_err(|e: toml::de::Error| PersistenceError::Config(e.message().to_string()))?;
+        let mut config = TimerConfig::default();
+        // Presets listed in the file replace the built-in ones.
+        if table.contains_key("presets") {
+            config.presets.clear();
+        }
+        for (section, values) in table {
+            let toml::Value::Table(values) = values else {
+                return Err(PersistenceError::Config(format!(
+                    "'{}' must be a [section]",
+                    section
+                )));
+            };
+            config.set_table(&section, values)?;
+        }
+        Ok(config)
+    }
+
+    /// Apply every value of a TOML table, nested tables included, as the
+    /// settings named `prefix.<name>`.
+    fn set_table(&mut self, prefix: &str, values: toml::Table) -> Result<(), PersistenceError> {
+        for (name, value) in values {
+            let key = format!("{}.{}", prefix, name);
+            let value = match value {
+                toml::Value::Table(values) => {
+                    self.set_table(&key, values)?;
+                    continue;
+                }
+                toml::Value::String(text) => text,
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                    value.to_string()
+                }
+                _ => return Err(invalid(&key, "expected a single value".to_string())),
+            };
+            self.set(&key, &value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Numbers and booleans are written bare, everything else as a string.
+fn toml_value(value: &str) -> String {
+    if value.parse::<i64>().is_ok() || value.parse::<bool>().is_ok() {
+        value.to_string()
+    } else {
+        toml::Value::String(value.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_set_and_reset() {
+        let mut config = TimerConfig::default();
+        assert_eq!(config.get("timer.work").unwrap(), "25m");
+
+        config.set("timer.work", "50m").unwrap();
+        config.set("timer.short_break", "1h 5m").unwrap();
+        config.set("goals.daily_focus", "6h").unwrap();
+        config.set("calendar.week_starts_on", "sunday").unwrap();
+        assert_eq!(config.pomodoro_work_duration, Duration::minutes(50));
+        assert_eq!(config.get("timer.short_break").unwrap(), "1h5m");
+        assert_eq!(config.goals.daily_focus, Some(Duration::hours(6)));
+        assert_eq!(config.calendar.week_starts_on, Weekday::Sun);
+
+        assert!(config.set("timer.work", "0m").is_err());
+        assert!(config.set("timer.long_break_interval", "0").is_err());
+        assert!(config.set("calendar.timezone", "Mars/Olympus").is_err());
+        assert!(config.set("timer.lunch", "1h").is_err());
+        assert_eq!(config.pomodoro_work_duration, Duration::minutes(50));
+        assert_eq!(config.calendar.timezone, None);
+
+        config.reset("timer.work").unwrap();
+        config.set("goals.daily_focus", "off").unwrap();
+        assert_eq!(config.pomodoro_work_duration, Duration::minutes(25));
+        assert_eq!(config.goals.daily_focus, None);
+
+        // Every listed key can be read back and set to its own value.
+        for (key, value) in TimerConfig::default().entries() {
+            config.set(key.name, &value).unwrap();
+        }
+        assert_eq!(config.entries().len(), KEYS.len());
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let mut config = TimerConfig::default();
+        config.set("timer.work", "50m").unwrap();
+        config.set("calendar.timezone", "Europe/Madrid").unwrap();
+        config.set("goals.daily_pomodoros", "8").unwrap();
+        config.set("reports.rounding_increment", "15").unwrap();
+        config
+            .set("hooks.on_stop", "notify-send \"Done: $NETUPI_PROJECT\"")
+            .unwrap();
+
+        let text = config.to_toml();
+        assert!(text.contains("[timer]\n# Length of a Pomodoro work session\nwork = \"50m\"\n"));
+        assert!(text.contains("daily_pomodoros = 8\n"));
+        assert!(text.contains("on_start = \"\"\n"));
+        let parsed = TimerConfig::from_toml(&text).unwrap();
+        assert_eq!(parsed.entries(), config.entries());
+        assert_eq!(
+            parsed.hooks.on_stop.as_deref(),
+            Some("notify-send \"Done: $NETUPI_PROJECT\"")
+        );
+        assert_eq!(parsed.hooks.on_start, None);
+
+        let edited = "[timer]\nwork = \"45m\" # longer\nlong_break_interval = 3\n";
+        let parsed = TimerConfig::from_toml(edited).unwrap();
+        assert_eq!(parsed.pomodoro_work_duration, Duration::minutes(45));
+        assert_eq!(parsed.long_break_interval, 3);
+        assert_eq!(parsed.pomodoro_short_break_duration, Duration::minutes(5));
+
+        assert!(TimerConfig::from_toml("[timer]\nwrok = \"45m\"\n").is_err());
+        assert!(TimerConfig::from_toml("[timer]\nwork = \"soon\"\n").is_err());
+        assert!(TimerConfig::from_toml("work = \"45m\"\n").is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        let mut config = TimerConfig::default();
+        assert_eq!(config.active_preset(), Some("classic"));
+
+        config.use_preset("deep").unwrap();
+        assert_eq!(config.pomodoro_work_duration, Duration::minutes(52));
+        assert_eq!(config.pomodoro_short_break_duration, Duration::minutes(17));
+        assert_eq!(config.active_preset(), Some("deep"));
+        assert!(config.use_preset("nap").is_err());
+
+        config.set("presets.admin.work", "20m").unwrap();
+        assert_eq!(
+            config.preset("admin").unwrap().short_break,
+            Duration::minutes(5)
+        );
+        config.set("timer.work", "40m").unwrap();
+        assert_eq!(config.active_preset(), None);
+        config.save_preset("long-focus").unwrap();
+        assert_eq!(config.active_preset(), Some("long-focus"));
+        assert!(config.save_preset("bad name").is_err());
+
+        // Presets survive the config file, and the file's list is authoritative.
+        config.remove_preset("classic").unwrap();
+        let parsed = TimerConfig::from_toml(&config.to_toml()).unwrap();
+        assert_eq!(parsed.presets, config.presets);
+        assert_eq!(
+            parsed.presets.keys().collect::<Vec<_>>(),
+            ["admin", "deep", "long-focus"]
+        );
+        let parsed = TimerConfig::from_toml("[timer]\nwork = \"30m\"\n").unwrap();
+        assert_eq!(parsed.presets, default_presets());
+    }
+}
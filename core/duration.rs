@@ -0,0 +1,155 @@
+use chrono::Duration;
+
+/// Parse a human-written duration such as "45m", "1h30m", "1h 30m", "90s",
+/// "1.5h" or "00:45:00". A bare number is taken as minutes.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let input = s.trim().to_lowercase();
+    let invalid = || {
+        format!(
+            "invalid duration '{}' (expected e.g. 45m, 1h30m or 90s)",
+            s.trim()
+        )
+    };
+    if input.is_empty() {
+        return Err(invalid());
+    }
+
+    let duration = if input.contains(':') {
+        parse_clock(&input).ok_or_else(invalid)?
+    } else if let Ok(minutes) = input.parse::<f64>() {
+        from_seconds(minutes * 60.0).ok_or_else(invalid)?
+    } else {
+        parse_units(&input).ok_or_else(invalid)?
+    };
+
+    if duration <= Duration::zero() {
+        return Err(format!("duration '{}' must be positive", s.trim()));
+    }
+    Ok(duration)
+}
+
+/// Parse a number of hours such as "10" or "7.5", zero or more.
+pub fn parse_hours(s: &str) -> Result<Duration, String> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .map(|hours| hours * 3600.0)
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .and_then(|seconds| Duration::try_seconds(seconds as i64))
+        .ok_or_else(|| {
+            format!(
+                "invalid hours '{}': expected a number of zero or more, like 10 or 7.5",
+                s
+            )
+        })
+}
+
+/// Format a duration the way [`parse_duration`] reads it, e.g. "25m",
+/// "1h30m" or "45s".
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let mut text = String::new();
+    if hours > 0 {
+        text.push_str(&format!("{}h", hours));
+    }
+    if minutes > 0 {
+        text.push_str(&format!("{}m", minutes));
+    }
+    if seconds > 0 || text.is_empty() {
+        text.push_str(&format!("{}s", seconds));
+    }
+    text
+}
+
+fn from_seconds(seconds: f64) -> Option<Duration> {
+    (seconds.is_finite() && seconds >= 0.0 && seconds < i64::MAX as f64 / 1000.0)
+        .then(|| Duration::milliseconds((seconds * 1000.0).round() as i64))
+}
+
+/// "MM:SS" or "HH:MM:SS".
+fn parse_clock(input: &str) -> Option<Duration> {
+    let parts: Vec<u32> = input
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [minutes, seconds] => (0, minutes, seconds),
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return None,
+    };
+    if seconds >= 60 || (parts.len() == 3 && minutes >= 60) {
+        return None;
+    }
+    Some(
+        Duration::hours(hours as i64)
+            + Duration::minutes(minutes as i64)
+            + Duration::seconds(seconds as i64),
+    )
+}
+
+/// A sequence of `<number><unit>` pairs, optionally separated by spaces.
+fn parse_units(input: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = rest[number_len..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_len] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            _ => return None,
+        };
+        total += number * seconds_per_unit;
+        rest = rest[unit_len..].trim_start();
+    }
+    from_seconds(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45m"), Ok(Duration::minutes(45)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("1h 30min"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::seconds(90)));
+        assert_eq!(parse_duration("25"), Ok(Duration::minutes(25)));
+        assert_eq!(parse_duration("1:05:30"), Ok(Duration::seconds(3930)));
+        assert_eq!(parse_duration("05:30"), Ok(Duration::seconds(330)));
+
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("10 parsecs").is_err());
+        assert!(parse_duration("1:75:00").is_err());
+        assert!(parse_duration("h").is_err());
+
+        for text in ["25m", "1h30m", "45s", "2h", "1h0m5s"] {
+            let duration = parse_duration(text).unwrap();
+            assert_eq!(parse_duration(&format_duration(duration)), Ok(duration));
+        }
+        assert_eq!(format_duration(Duration::minutes(90)), "1h30m");
+        assert_eq!(format_duration(Duration::zero()), "0s");
+    }
+
+    #[test]
+    fn test_parse_hours() {
+        assert_eq!(parse_hours("10"), Ok(Duration::hours(10)));
+        assert_eq!(parse_hours("7.5"), Ok(Duration::minutes(450)));
+        assert_eq!(parse_hours("0"), Ok(Duration::zero()));
+        for bad in ["-1", "inf", "NaN", "1e20", "ten", ""] {
+            assert!(parse_hours(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::calendar::Calendar;
+use crate::{TimerConfig, TimerType, WorkSession};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Daily and weekly targets, stored in the config. Unset goals are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Goals {
+    /// Focused work (stopwatch and Pomodoro work time) per day.
+    pub daily_focus: Option<Duration>,
+    /// Completed Pomodoro work sessions per day.
+    pub daily_pomodoros: Option<u32>,
+    /// Focused work per calendar week.
+    pub weekly_focus: Option<Duration>,
+    /// Only count Monday to Friday for daily goals; weekends neither
+    /// count towards nor break a streak.
+    pub weekdays_only: bool,
+}
+
+impl Goals {
+    pub fn is_empty(&self) -> bool {
+        self.daily_focus.is_none() && self.daily_pomodoros.is_none() && self.weekly_focus.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalKind {
+    DailyFocus,
+    DailyPomodoros,
+    WeeklyFocus,
+}
+
+/// Progress towards a goal in the current day or week, plus streaks of
+/// consecutive days (or weeks) that met it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub kind: GoalKind,
+    /// Target and achieved amounts: seconds for time goals, a count for
+    /// Pomodoro goals.
+    pub target: i64,
+    pub achieved: i64,
+    /// False when today is a weekend day and the goal is weekdays-only.
+    pub applies_today: bool,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+impl GoalProgress {
+    pub fn fraction(&self) -> f64 {
+        if self.target <= 0 {
+            return 1.0;
+        }
+        self.achieved as f64 / self.target as f64
+    }
+
+    pub fn is_met(&self) -> bool {
+        self.achieved >= self.target
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DayTotals {
+    pub focus: Duration,
+    pub pomodoros: u32,
+}
+
+/// Whether a Pomodoro work session ran for its full length: the one it was
+/// started with, or the configured one for sessions saved before that was
+/// recorded.
+pub fn is_completed_pomodoro(session: &WorkSession, config: &TimerConfig) -> bool {
+    session.session_type == TimerType::PomodoroWork
+        && session.end_time.is_some()
+        && session.duration
+            >= session
+                .target_duration
+                .unwrap_or(config.pomodoro_work_duration)
+}
+
+/// Pomodoros completed in the current cycle: those since the last long
+/// break that started after `since`, at most `long_break_interval` (a long
+/// break is due), which comes from the cycle's preset.
+pub fn cycle_position(
+    sessions: &[WorkSession],
+    config: &TimerConfig,
+    long_break_interval: u32,
+    since: DateTime<Utc>,
+) -> u32 {
+    let mut sessions: Vec<&WorkSession> =
+        sessions.iter().filter(|s| s.start_time >= since).collect();
+    sessions.sort_by_key(|s| s.start_time);
+    let mut completed = 0;
+    for session in sessions {
+        if session.session_type == TimerType::PomodoroLongBreak {
+            completed = 0;
+        } else if is_completed_pomodoro(session, config) {
+            completed += 1;
+        }
+    }
+    completed.min(long_break_interval)
+}
+
+/// Focused time and completed Pomodoros per calendar day. Sessions spanning
+/// several days are split between them; a Pomodoro counts on the day it ended.
+pub fn daily_totals(
+    sessions: &[WorkSession],
+    config: &TimerConfig,
+    calendar: &Calendar,
+) -> BTreeMap<NaiveDate, DayTotals> {
+    let mut days: BTreeMap<NaiveDate, DayTotals> = BTreeMap::new();
+    for session in sessions {
+        let Some(end) = session.end_time else {
+            continue;
+        };
+        if !matches!(
+            session.session_type,
+            TimerType::Stopwatch | TimerType::PomodoroWork
+        ) {
+            continue;
+        }
+
+        for (day, part) in calendar.split_by_day(session) {
+            days.entry(day).or_default().focus += part.duration;
+        }
+        if is_completed_pomodoro(session, config) {
+            days.entry(calendar.day_of(end)).or_default().pomodoros += 1;
+        }
+    }
+    days
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Current and longest runs of consecutive periods meeting a goal.
+/// `periods` yields `(met, counts)` from oldest to newest, where periods
+/// that don't count (`counts == false`) are skipped without breaking a
+/// streak. The newest period is still in progress, so missing it doesn't
+/// break the current streak either.
+fn streaks(periods: &[(bool, bool)]) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    for &(met, counts) in periods {
+        if !counts {
+            continue;
+        }
+        if met {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+
+    let mut current = 0;
+    let mut counted = periods
+        .iter()
+        .rev()
+        .filter(|(_, counts)| *counts)
+        .peekable();
+    if counted.peek().is_some_and(|(met, _)| !met) {
+        counted.next();
+    }
+    for (met, _) in counted {
+        if !met {
+            break;
+        }
+        current += 1;
+    }
+    (current, longest)
+}
+
+/// Evaluate every configured goal against the session history, as of `today`.
+pub fn progress(
+    sessions: &[WorkSession],
+    config: &TimerConfig,
+    calendar: &Calendar,
+    today: NaiveDate,
+) -> Vec<GoalProgress> {
+    let goals = &config.goals;
+    let days = daily_totals(sessions, config, calendar);
+    let first_day = days.keys().next().copied().unwrap_or(today).min(today);
+    let today_totals = days.get(&today).copied().unwrap_or_default();
+    let counts_today = !(goals.weekdays_only && is_weekend(today));
+
+    let daily = |target: i64, achieved: fn(&DayTotals) -> i64, kind: GoalKind| {
+        let periods: Vec<(bool, bool)> = first_day
+            .iter_days()
+            .take_while(|d| *d <= today)
+            .map(|d| {
+                let totals = days.get(&d).copied().unwrap_or_default();
+                (
+                    achieved(&totals) >= target,
+                    !(goals.weekdays_only && is_weekend(d)),
+                )
+            })
+            .collect();
+        let (current_streak, longest_streak) = streaks(&periods);
+        GoalProgress {
+            kind,
+            target,
+            achieved: achieved(&today_totals),
+            applies_today: counts_today,
+            current_streak,
+            longest_streak,
+        }
+    };
+
+    let mut result = Vec::new();
+    if let Some(target) = goals.daily_focus {
+        result.push(daily(
+            target.num_seconds(),
+            |t| t.focus.num_seconds(),
+            GoalKind::DailyFocus,
+        ));
+    }
+    if let Some(target) = goals.daily_pomodoros {
+        result.push(daily(
+            target as i64,
+            |t| t.pomodoros as i64,
+            GoalKind::DailyPomodoros,
+        ));
+    }
+    if let Some(target) = goals.weekly_focus {
+        let mut weeks: BTreeMap<NaiveDate, Duration> = BTreeMap::new();
+        let mut week = calendar.week_of(first_day);
+        while week <= today {
+            weeks.insert(week, Duration::zero());
+            week += Duration::weeks(1);
+        }
+        for (day, totals) in &days {
+            *weeks
+                .entry(calendar.week_of(*day))
+                .or_insert(Duration::zero()) += totals.focus;
+        }
+        let periods: Vec<(bool, bool)> = weeks
+            .range(..=today)
+            .map(|(_, focus)| (*focus >= target, true))
+            .collect();
+        let (current_streak, longest_streak) = streaks(&periods);
+        result.push(GoalProgress {
+            kind: GoalKind::WeeklyFocus,
+            target: target.num_seconds(),
+            achieved: weeks
+                .get(&calendar.week_of(today))
+                .map(|d| d.num_seconds())
+                .unwrap_or(0),
+            applies_today: true,
+            current_streak,
+            longest_streak,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SessionBuilder;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_daily_focus_streaks_skip_weekends() {
+        let config = TimerConfig {
+            goals: Goals {
+                daily_focus: Some(Duration::hours(6)),
+                weekdays_only: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // 2026-03-02 is a Monday.
+        let sessions: Vec<WorkSession> = [(2, 6), (3, 7), (4, 2), (5, 6), (6, 6), (9, 8), (10, 3)]
+            .into_iter()
+            .map(|(day, hours)| {
+                let end = Utc.with_ymd_and_hms(2026, 3, day, 18, 0, 0).unwrap();
+                SessionBuilder::ending(end, Duration::hours(hours))
+                    .project("api")
+                    .build()
+            })
+            .collect();
+
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let goal = &progress(&sessions, &config, &Calendar::utc(), today)[0];
+        assert_eq!(goal.kind, GoalKind::DailyFocus);
+        assert_eq!(goal.achieved, Duration::hours(3).num_seconds());
+        assert!(!goal.is_met());
+        // Thu, Fri, (weekend skipped), Mon; today is still in progress.
+        assert_eq!(goal.current_streak, 3);
+        assert_eq!(goal.longest_streak, 3);
+
+        let tomorrow = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+        let goal = &progress(&sessions, &config, &Calendar::utc(), tomorrow)[0];
+        assert_eq!(goal.current_streak, 0);
+    }
+
+    #[test]
+    fn test_cycle_position_resets_after_long_breaks() {
+        let config = TimerConfig::default();
+        let at = |day: u32, hour: u32, session_type: TimerType, minutes: i64| {
+            let start = Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+            SessionBuilder::starting(start, Duration::minutes(minutes))
+                .kind(session_type)
+                .build()
+        };
+        let mut sessions = vec![
+            at(2, 9, TimerType::PomodoroWork, 25),
+            at(3, 9, TimerType::PomodoroWork, 25),
+            at(3, 10, TimerType::PomodoroWork, 10),
+            at(3, 11, TimerType::PomodoroShortBreak, 5),
+            at(3, 12, TimerType::PomodoroWork, 25),
+        ];
+        let since = Utc.with_ymd_and_hms(2026, 3, 3, 0, 0, 0).unwrap();
+        // Yesterday's and unfinished Pomodoros don't count.
+        assert_eq!(cycle_position(&sessions, &config, 4, since), 2);
+
+        sessions.push(at(3, 13, TimerType::PomodoroLongBreak, 15));
+        sessions.push(at(3, 14, TimerType::PomodoroWork, 25));
+        assert_eq!(cycle_position(&sessions, &config, 4, since), 1);
+
+        for hour in 15..20 {
+            sessions.push(at(3, hour, TimerType::PomodoroWork, 25));
+        }
+        assert_eq!(cycle_position(&sessions, &config, 4, since), 4);
+        // A preset with a longer cycle.
+        assert_eq!(cycle_position(&sessions, &config, 6, since), 6);
+    }
+}
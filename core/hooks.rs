@@ -0,0 +1,404 @@
+use crate::duration::format_duration;
+use crate::{NetupiCore, TimerEvent, TimerType, WorkSession};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::oneshot;
+use tokio::task::{JoinHandle, JoinSet};
+
+// A hook is a shell command run when a timer event happens. It gets the
+// session details as environment variables:
+//
+//   NETUPI_HOOK          on_start, on_stop, ...
+//   NETUPI_TIMER         pomodoro, short_break, long_break, stopwatch or countdown
+//   NETUPI_SESSION_ID
+//   NETUPI_PROJECT       empty when the session has no project
+//   NETUPI_DESCRIPTION   the description, or the countdown label
+//   NETUPI_ELAPSED       seconds timed so far, or in total once stopped
+//   NETUPI_TARGET        seconds the timer counts down from, if it does
+//
+// and the same as JSON on stdin: {"hook", "event", "session", "elapsed_seconds"}.
+// Hooks run in the background; a failing or timed-out hook is logged to
+// hooks.log in the data directory and never affects the timer.
+
+/// A point in a timer's life a command can be attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    Complete,
+    /// Replaces `Start` when the timer is a Pomodoro break.
+    BreakStart,
+}
+
+impl Hook {
+    pub const ALL: [Hook; 6] = [
+        Hook::Start,
+        Hook::Stop,
+        Hook::Pause,
+        Hook::Resume,
+        Hook::Complete,
+        Hook::BreakStart,
+    ];
+
+    /// The setting name, e.g. "on_start".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::Start => "on_start",
+            Hook::Stop => "on_stop",
+            Hook::Pause => "on_pause",
+            Hook::Resume => "on_resume",
+            Hook::Complete => "on_complete",
+            Hook::BreakStart => "on_break_start",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Hook> {
+        Hook::ALL.into_iter().find(|hook| hook.name() == name)
+    }
+
+    /// The hook an event triggers, given the type of the timer it's about.
+    fn for_event(event: &TimerEvent, timer_type: Option<TimerType>) -> Option<Hook> {
+        match event {
+            TimerEvent::Started => match timer_type {
+                Some(TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak) => {
+                    Some(Hook::BreakStart)
+                }
+                _ => Some(Hook::Start),
+            },
+            TimerEvent::Stopped => Some(Hook::Stop),
+            TimerEvent::Paused => Some(Hook::Pause),
+            TimerEvent::Resumed => Some(Hook::Resume),
+            TimerEvent::Completed { .. } => Some(Hook::Complete),
+            TimerEvent::Tick(_) | TimerEvent::BudgetThreshold { .. } => None,
+        }
+    }
+}
+
+/// Commands to run on timer events, one per [`Hook`]. `None` runs nothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub on_start: Option<String>,
+    pub on_stop: Option<String>,
+    pub on_pause: Option<String>,
+    pub on_resume: Option<String>,
+    pub on_complete: Option<String>,
+    pub on_break_start: Option<String>,
+    /// How long a hook may run before it's killed.
+    pub timeout: Duration,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_start: None,
+            on_stop: None,
+            on_pause: None,
+            on_resume: None,
+            on_complete: None,
+            on_break_start: None,
+            timeout: Duration::seconds(10),
+        }
+    }
+}
+
+impl HooksConfig {
+    pub fn command(&self, hook: Hook) -> Option<&str> {
+        match hook {
+            Hook::Start => self.on_start.as_deref(),
+            Hook::Stop => self.on_stop.as_deref(),
+            Hook::Pause => self.on_pause.as_deref(),
+            Hook::Resume => self.on_resume.as_deref(),
+            Hook::Complete => self.on_complete.as_deref(),
+            Hook::BreakStart => self.on_break_start.as_deref(),
+        }
+    }
+
+    pub fn set_command(&mut self, hook: Hook, command: Option<String>) {
+        let slot = match hook {
+            Hook::Start => &mut self.on_start,
+            Hook::Stop => &mut self.on_stop,
+            Hook::Pause => &mut self.on_pause,
+            Hook::Resume => &mut self.on_resume,
+            Hook::Complete => &mut self.on_complete,
+            Hook::BreakStart => &mut self.on_break_start,
+        };
+        *slot = command;
+    }
+}
+
+/// Short timer names, the same as the `timer` field of a `StartRequest`.
+pub fn timer_name(timer_type: TimerType) -> &'static str {
+    match timer_type {
+        TimerType::Stopwatch => "stopwatch",
+        TimerType::PomodoroWork => "pomodoro",
+        TimerType::PomodoroShortBreak => "short_break",
+        TimerType::PomodoroLongBreak => "long_break",
+        TimerType::CustomTimer => "countdown",
+    }
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    hook: &'static str,
+    event: &'a TimerEvent,
+    session: Option<&'a WorkSession>,
+    elapsed_seconds: Option<i64>,
+}
+
+/// Runs the configured hooks for every timer event of a core, in the
+/// background, until [`HookRunner::finish`].
+pub struct HookRunner {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl HookRunner {
+    pub fn spawn(core: &NetupiCore) -> Self {
+        let core = core.clone();
+        let mut events = core.timer().subscribe_events();
+        let (stop, mut stopped) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            let mut hooks = JoinSet::new();
+            // The last session seen, to describe it once it has stopped.
+            let mut last = None;
+            loop {
+                tokio::select! {
+                    Some(event) = events.recv() => {
+                        handle_event(&core, event, &mut last, &mut hooks).await;
+                        while hooks.try_join_next().is_some() {}
+                    }
+                    _ = &mut stopped => break,
+                }
+            }
+            while let Ok(event) = events.try_recv() {
+                handle_event(&core, event, &mut last, &mut hooks).await;
+            }
+            hooks.join_all().await;
+        });
+
+        Self { stop, task }
+    }
+
+    /// Run the hooks of events already emitted and wait for every running
+    /// hook to exit, e.g. before the program quits.
+    pub async fn finish(self) {
+        let _ = self.stop.send(());
+        let _ = self.task.await;
+    }
+}
+
+async fn handle_event(
+    core: &NetupiCore,
+    event: TimerEvent,
+    last: &mut Option<WorkSession>,
+    hooks: &mut JoinSet<()>,
+) {
+    let current = core.timer().current_session().await;
+    let (session, elapsed) = match (&event, current) {
+        // By now another session may have started, so look up the one
+        // that stopped, as saved.
+        (TimerEvent::Stopped, _) | (TimerEvent::Completed { .. }, None) => {
+            let saved =
+                match last.as_ref() {
+                    Some(session) => core.get_sessions().await.ok().and_then(|sessions| {
+                        sessions.into_iter().rev().find(|s| s.id == session.id)
+                    }),
+                    None => None,
+                };
+            if let Some(saved) = saved {
+                *last = Some(saved);
+            }
+            let elapsed = last.as_ref().map(|session| session.duration);
+            (last.clone(), elapsed)
+        }
+        (_, Some(session)) => {
+            *last = Some(session.clone());
+            let elapsed = core.timer().get_current_state().await.elapsed;
+            (Some(session), Some(elapsed))
+        }
+        (_, None) => (None, None),
+    };
+
+    let timer_type = session.as_ref().map(|s| s.session_type);
+    let Some(hook) = Hook::for_event(&event, timer_type) else {
+        return;
+    };
+    let config = core.timer().get_config().await.hooks;
+    let Some(command) = config.command(hook) else {
+        return;
+    };
+
+    let mut env = vec![("NETUPI_HOOK", hook.name().to_string())];
+    if let Some(session) = &session {
+        env.extend([
+            ("NETUPI_TIMER", timer_name(session.session_type).to_string()),
+            ("NETUPI_SESSION_ID", session.id.to_string()),
+            (
+                "NETUPI_PROJECT",
+                session.tags.first().cloned().unwrap_or_default(),
+            ),
+            (
+                "NETUPI_DESCRIPTION",
+                session.description.clone().unwrap_or_default(),
+            ),
+        ]);
+        if let Some(target) = session.target_duration {
+            env.push(("NETUPI_TARGET", target.num_seconds().to_string()));
+        }
+    }
+    if let Some(elapsed) = elapsed {
+        env.push(("NETUPI_ELAPSED", elapsed.num_seconds().to_string()));
+    }
+    let payload = serde_json::to_string(&Payload {
+        hook: hook.name(),
+        event: &event,
+        session: session.as_ref(),
+        elapsed_seconds: elapsed.map(|e| e.num_seconds()),
+    })
+    .unwrap_or_default();
+
+    let command = command.to_string();
+    let log_file = core.persistence().hooks_log_file();
+    hooks.spawn(async move {
+        if let Err(error) = run(&command, env, payload, config.timeout).await {
+            log_failure(&log_file, hook, &command, &error).await;
+        }
+    });
+}
+
+/// Run `command` through the shell and wait for it to exit successfully.
+async fn run(
+    command: &str,
+    env: Vec<(&'static str, String)>,
+    payload: String,
+    timeout: Duration,
+) -> Result<(), String> {
+    let mut child = shell(command)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("could not start: {}", e))?;
+
+    // Hooks that don't read stdin close it early; that's not an error.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+
+    let output = tokio::time::timeout(
+        timeout.to_std().unwrap_or_default(),
+        child.wait_with_output(),
+    )
+    .await
+    .map_err(|_| format!("killed after {}", format_duration(timeout)))?
+    .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.trim() {
+        "" => Err(format!("exited with {}", output.status)),
+        stderr => Err(format!("exited with {}: {}", output.status, stderr)),
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+async fn log_failure(path: &Path, hook: Hook, command: &str, error: &str) {
+    let line = format!(
+        "{} {} `{}` {}\n",
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+        hook.name(),
+        command,
+        error
+    );
+    let file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await;
+    if let Ok(mut file) = file {
+        // tokio only writes in the background until flushed.
+        let _ = file.write_all(line.as_bytes()).await;
+        let _ = file.flush().await;
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::testing::test_core;
+
+    #[tokio::test]
+    async fn test_hooks_run_on_timer_events() {
+        let (dir, core) = test_core().await;
+        let out = dir.path().join("hooks.out");
+
+        let mut config = core.timer().get_config().await;
+        let record = format!(
+            "echo \"$NETUPI_HOOK $NETUPI_TIMER $NETUPI_PROJECT $(cat)\" >> {}",
+            out.display()
+        );
+        config.hooks.on_start = Some(record.clone());
+        config.hooks.on_stop = Some(record.clone());
+        config.hooks.on_break_start = Some(record);
+        config.hooks.on_pause = Some("echo 'no focus mode' >&2; exit 3".to_string());
+        config.hooks.on_resume = Some("sleep 5".to_string());
+        config.hooks.timeout = Duration::milliseconds(200);
+        core.timer().set_config(config).await.unwrap();
+
+        // Hooks see the timer as it is when they handle an event, so give
+        // them time to catch up between steps.
+        let settle = || tokio::time::sleep(std::time::Duration::from_millis(50));
+        let hooks = HookRunner::spawn(&core);
+        core.start_pomodoro(Some("api".to_string()), None, None)
+            .await
+            .unwrap();
+        settle().await;
+        core.timer().pause_timer().await;
+        core.timer().resume_timer().await;
+        settle().await;
+        core.timer().stop_timer().await.unwrap();
+        settle().await;
+        core.timer()
+            .start_timer(TimerType::PomodoroShortBreak)
+            .await
+            .unwrap();
+        hooks.finish().await;
+
+        let out = std::fs::read_to_string(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("on_start pomodoro api {\"hook\":\"on_start\""));
+        assert!(lines[1].starts_with("on_stop pomodoro api "));
+        assert!(lines[1].contains("\"elapsed_seconds\":0"));
+        assert!(lines[2].starts_with("on_break_start short_break api "));
+
+        let log = std::fs::read_to_string(dir.path().join("hooks.log")).unwrap();
+        assert!(log.contains("on_pause `echo 'no focus mode' >&2; exit 3` exited with"));
+        assert!(log.contains(": no focus mode\n"));
+        assert!(log.contains("on_resume `sleep 5` killed after"));
+    }
+}
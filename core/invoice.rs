@@ -0,0 +1,535 @@
+use crate::billing;
+use crate::calendar::Calendar;
+use crate::rounding::Rounding;
+use crate::{ProjectSettings, WorkSession};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// How billable sessions are collapsed into invoice line items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvoiceGrouping {
+    #[default]
+    Day,
+    Task,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvoiceFormat {
+    #[default]
+    Markdown,
+    Html,
+    Text,
+}
+
+#[derive(Debug, Clone)]
+pub struct InvoiceOptions {
+    pub project: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub grouping: InvoiceGrouping,
+    /// Rounding rule; the project's configured rule is used when `None`.
+    pub rounding: Option<Rounding>,
+    /// Invoice number; generated from the existing invoices when `None`.
+    pub number: Option<String>,
+    /// Build the invoice without marking its sessions as invoiced.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceLine {
+    pub label: String,
+    pub duration: Duration,
+    pub rate: f64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub number: String,
+    pub project: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub issued: DateTime<Utc>,
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+    pub total_duration: Duration,
+    pub total_amount: f64,
+    pub session_ids: Vec<uuid::Uuid>,
+}
+
+/// Whether a session is eligible for an invoice on `project` over the period:
+/// a completed, billable, not yet invoiced work session whose end date
+/// (as a calendar day) falls within `from..=to`.
+pub fn is_invoiceable(
+    session: &WorkSession,
+    project: &str,
+    settings: Option<&ProjectSettings>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    calendar: &Calendar,
+) -> bool {
+    let Some(end) = session.end_time else {
+        return false;
+    };
+    let date = calendar.day_of(end);
+
+    session.project() == Some(project)
+        && session.invoice.is_none()
+        && billing::is_billable(session, settings)
+        && from.is_none_or(|from| date >= from)
+        && to.is_none_or(|to| date <= to)
+}
+
+/// Group the invoiceable sessions into line items, applying rates and
+/// rounding either each session or each line's total time.
+pub fn build_invoice(
+    sessions: &[WorkSession],
+    settings: Option<&ProjectSettings>,
+    options: &InvoiceOptions,
+    number: String,
+    calendar: &Calendar,
+) -> Invoice {
+    // Key by label and rate so that sessions with a rate override end up on
+    // their own line instead of being averaged into the project rate.
+    let mut groups: BTreeMap<(String, u64), Duration> = BTreeMap::new();
+    let mut session_ids = Vec::new();
+    let rounding = options.rounding.unwrap_or_default();
+
+    for session in sessions.iter().filter(|s| {
+        is_invoiceable(
+            s,
+            &options.project,
+            settings,
+            options.from,
+            options.to,
+            calendar,
+        )
+    }) {
+        let label = match options.grouping {
+            InvoiceGrouping::Day => session
+                .end_time
+                .map(|end| calendar.day_of(end).to_string())
+                .unwrap_or_default(),
+            InvoiceGrouping::Task => session
+                .description
+                .clone()
+                .unwrap_or_else(|| "General work".to_string()),
+        };
+        let rate = billing::hourly_rate(session, settings).unwrap_or(0.0);
+        *groups
+            .entry((label, rate.to_bits()))
+            .or_insert(Duration::zero()) += rounding.apply_to_session(session.duration);
+        session_ids.push(session.id);
+    }
+
+    let lines: Vec<InvoiceLine> = groups
+        .into_iter()
+        .map(|((label, rate), duration)| {
+            let rate = f64::from_bits(rate);
+            let duration = rounding.apply_to_total(duration);
+            InvoiceLine {
+                label,
+                duration,
+                rate,
+                amount: billing::earnings(duration, rate),
+            }
+        })
+        .collect();
+
+    Invoice {
+        number,
+        project: options.project.clone(),
+        from: options.from,
+        to: options.to,
+        issued: Utc::now(),
+        currency: billing::currency(settings),
+        total_duration: lines.iter().map(|l| l.duration).sum(),
+        total_amount: lines.iter().map(|l| l.amount).sum(),
+        lines,
+        session_ids,
+    }
+}
+
+/// Next sequential invoice number: one past the highest `INV-####` number
+/// recorded on sessions. Numbers in other formats, given with `--number`,
+/// are left out.
+pub fn next_invoice_number(sessions: &[WorkSession]) -> String {
+    let highest = sessions
+        .iter()
+        .filter_map(|s| {
+            s.invoice
+                .as_deref()?
+                .strip_prefix("INV-")?
+                .parse::<u32>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0);
+    format!("INV-{:04}", highest + 1)
+}
+
+fn hours(duration: Duration) -> String {
+    format!("{:.2}", duration.num_seconds() as f64 / 3600.0)
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Keep a value inside its Markdown table cell.
+fn escape_markdown(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Render an invoice with a template. Scalar placeholders are written as
+/// `{{name}}`; the text between `{{#lines}}` and `{{/lines}}` is repeated
+/// once per line item with `{{label}}`, `{{hours}}`, `{{rate}}` and
+/// `{{amount}}` available. The issue date is the day in `calendar`.
+pub fn render(
+    invoice: &Invoice,
+    template: &str,
+    format: InvoiceFormat,
+    calendar: &Calendar,
+) -> String {
+    let escape = |value: &str| match format {
+        InvoiceFormat::Html => escape_html(value),
+        InvoiceFormat::Markdown => escape_markdown(value),
+        InvoiceFormat::Text => value.to_string(),
+    };
+    let date = |d: Option<NaiveDate>| d.map(|d| d.to_string()).unwrap_or_else(|| "—".into());
+    let scalar = |name: &str| {
+        Some(match name {
+            "number" => escape(&invoice.number),
+            "project" => escape(&invoice.project),
+            "from" => date(invoice.from),
+            "to" => date(invoice.to),
+            "date" => calendar
+                .local(invoice.issued)
+                .format("%Y-%m-%d")
+                .to_string(),
+            "currency" => escape(&invoice.currency),
+            "total_hours" => hours(invoice.total_duration),
+            "total_amount" => format!("{:.2}", invoice.total_amount),
+            _ => return None,
+        })
+    };
+
+    let (start, end) = match (template.find("{{#lines}}"), template.find("{{/lines}}")) {
+        (Some(start), Some(end)) if start < end => (start, end),
+        _ => return fill(template, scalar),
+    };
+    let row = &template[start + "{{#lines}}".len()..end];
+    let mut output = fill(&template[..start], scalar);
+    for line in &invoice.lines {
+        output.push_str(&fill(row, |name| match name {
+            "label" => Some(escape(&line.label)),
+            "hours" => Some(hours(line.duration)),
+            "rate" => Some(format!("{:.2}", line.rate)),
+            "amount" => Some(format!("{:.2}", line.amount)),
+            _ => scalar(name),
+        }));
+    }
+    output.push_str(&fill(&template[end + "{{/lines}}".len()..], scalar));
+    output
+}
+
+/// Replace the `{{name}}` placeholders `value` knows in one pass, so a
+/// value that looks like a placeholder itself is left as it is.
+fn fill(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        output.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let filled = rest
+            .find("}}")
+            .and_then(|close| Some((value(&rest[2..close])?, close)));
+        match filled {
+            Some((text, close)) => {
+                output.push_str(&text);
+                rest = &rest[close + 2..];
+            }
+            None => {
+                output.push_str("{{");
+                rest = &rest[2..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+impl InvoiceFormat {
+    /// File name of the user-editable template, stored next to `config.toml`.
+    pub fn template_file_name(&self) -> &'static str {
+        match self {
+            Self::Markdown => "invoice_template.md",
+            Self::Html => "invoice_template.html",
+            Self::Text => "invoice_template.txt",
+        }
+    }
+
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            Self::Markdown => DEFAULT_MARKDOWN_TEMPLATE,
+            Self::Html => DEFAULT_HTML_TEMPLATE,
+            Self::Text => DEFAULT_TEXT_TEMPLATE,
+        }
+    }
+}
+
+impl FromStr for InvoiceGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" => Ok(Self::Day),
+            "task" => Ok(Self::Task),
+            other => Err(format!(
+                "unknown grouping '{}' (expected day or task)",
+                other
+            )),
+        }
+    }
+}
+
+impl FromStr for InvoiceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            "text" | "txt" => Ok(Self::Text),
+            other => Err(format!(
+                "unknown invoice format '{}' (expected markdown, html or text)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for InvoiceGrouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Day => "day",
+            Self::Task => "task",
+        })
+    }
+}
+
+impl fmt::Display for InvoiceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+            Self::Text => "text",
+        })
+    }
+}
+
+const DEFAULT_MARKDOWN_TEMPLATE: &str = "\
+# Invoice {{number}}
+
+**Project:** {{project}}
+**Period:** {{from}} – {{to}}
+**Date:** {{date}}
+
+| Item | Hours | Rate ({{currency}}) | Amount ({{currency}}) |
+|------|------:|-----:|-------:|
+{{#lines}}| {{label}} | {{hours}} | {{rate}} | {{amount}} |
+{{/lines}}
+**Total:** {{total_hours}} h — **{{total_amount}} {{currency}}**
+";
+
+const DEFAULT_HTML_TEMPLATE: &str = "\
+<!DOCTYPE html>
+<html>
+<head><meta charset=\"utf-8\"><title>Invoice {{number}}</title></head>
+<body>
+<h1>Invoice {{number}}</h1>
+<p><strong>Project:</strong> {{project}}<br>
+<strong>Period:</strong> {{from}} – {{to}}<br>
+<strong>Date:</strong> {{date}}</p>
+<table>
+<tr><th>Item</th><th>Hours</th><th>Rate ({{currency}})</th><th>Amount ({{currency}})</th></tr>
+{{#lines}}<tr><td>{{label}}</td><td>{{hours}}</td><td>{{rate}}</td><td>{{amount}}</td></tr>
+{{/lines}}</table>
+<p><strong>Total:</strong> {{total_hours}} h — <strong>{{total_amount}} {{currency}}</strong></p>
+</body>
+</html>
+";
+
+const DEFAULT_TEXT_TEMPLATE: &str = "\
+INVOICE {{number}}
+
+Project: {{project}}
+Period:  {{from}} - {{to}}
+Date:    {{date}}
+
+{{#lines}}{{label}}: {{hours}} h x {{rate}} = {{amount}} {{currency}}
+{{/lines}}
+Total: {{total_hours}} h = {{total_amount}} {{currency}}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rounding::RoundingMode;
+    use crate::testing::SessionBuilder;
+    use chrono::TimeZone;
+
+    /// A session on `client` that ended at 17:00 on the given March day.
+    fn on(day: u32, minutes: i64, description: &str) -> SessionBuilder {
+        let end = Utc.with_ymd_and_hms(2026, 3, day, 17, 0, 0).unwrap();
+        SessionBuilder::ending(end, Duration::minutes(minutes))
+            .project("client")
+            .description(description)
+    }
+
+    #[test]
+    fn test_build_and_render_invoice() {
+        let settings = ProjectSettings {
+            billable: true,
+            hourly_rate: Some(100.0),
+            currency: Some("EUR".to_string()),
+            rounding: None,
+            budget: None,
+        };
+        let sessions = vec![
+            on(2, 50, "api").build(),
+            on(2, 20, "docs").build(),
+            on(3, 7, "api").build(),
+            on(9, 60, "out of range").build(),
+            on(2, 60, "api").invoice("INV-0001").build(),
+        ];
+
+        let options = InvoiceOptions {
+            project: "client".to_string(),
+            from: NaiveDate::from_ymd_opt(2026, 3, 1),
+            to: NaiveDate::from_ymd_opt(2026, 3, 5),
+            grouping: InvoiceGrouping::Day,
+            rounding: Some(Rounding::new(RoundingMode::Up, 15)),
+            number: None,
+            dry_run: false,
+        };
+        let number = next_invoice_number(&sessions);
+        assert_eq!(number, "INV-0002");
+
+        let invoice = build_invoice(
+            &sessions,
+            Some(&settings),
+            &options,
+            number,
+            &Calendar::utc(),
+        );
+        assert_eq!(invoice.session_ids.len(), 3);
+        assert_eq!(invoice.lines.len(), 2);
+        assert_eq!(invoice.lines[0].label, "2026-03-02");
+        assert_eq!(invoice.lines[0].duration, Duration::minutes(75));
+        assert_eq!(invoice.lines[1].duration, Duration::minutes(15));
+        assert!((invoice.total_amount - 150.0).abs() < 1e-9);
+
+        let text = render(
+            &invoice,
+            InvoiceFormat::Text.default_template(),
+            InvoiceFormat::Text,
+            &Calendar::utc(),
+        );
+        assert!(text.contains("INVOICE INV-0002"));
+        assert!(text.contains("2026-03-02: 1.25 h x 100.00 = 125.00 EUR"));
+        assert!(text.contains("Total: 1.50 h = 150.00 EUR"));
+    }
+
+    #[test]
+    fn test_next_invoice_number_follows_the_highest() {
+        let sessions: Vec<WorkSession> = ["INV-0001", "INV-0003", "ACME-17"]
+            .into_iter()
+            .map(|number| on(2, 60, "api").invoice(number).build())
+            .collect();
+        // Counting the three numbers would give INV-0004 a second time.
+        assert_eq!(next_invoice_number(&sessions), "INV-0004");
+        assert_eq!(next_invoice_number(&[]), "INV-0001");
+    }
+
+    #[test]
+    fn test_markdown_labels_are_escaped() {
+        let settings = ProjectSettings {
+            billable: true,
+            hourly_rate: Some(100.0),
+            currency: None,
+            rounding: None,
+            budget: None,
+        };
+        let options = InvoiceOptions {
+            project: "client".to_string(),
+            from: None,
+            to: None,
+            grouping: InvoiceGrouping::Task,
+            rounding: None,
+            number: None,
+            dry_run: false,
+        };
+        let sessions = [on(2, 60, "api | auth").build()];
+        let invoice = build_invoice(
+            &sessions,
+            Some(&settings),
+            &options,
+            "INV-0001".to_string(),
+            &Calendar::utc(),
+        );
+        let markdown = render(
+            &invoice,
+            InvoiceFormat::Markdown.default_template(),
+            InvoiceFormat::Markdown,
+            &Calendar::utc(),
+        );
+        assert!(markdown.contains("| api \\| auth | 1.00 |"));
+    }
+
+    #[test]
+    fn test_render_fills_each_placeholder_once() {
+        let options = InvoiceOptions {
+            project: "client".to_string(),
+            from: None,
+            to: None,
+            grouping: InvoiceGrouping::Task,
+            rounding: None,
+            number: None,
+            dry_run: false,
+        };
+        let sessions = [on(2, 60, "{{currency}}").billable(true).build()];
+        let mut invoice = build_invoice(
+            &sessions,
+            None,
+            &options,
+            "INV-0001".to_string(),
+            &Calendar::utc(),
+        );
+        invoice.project = "{{total_amount}}".to_string();
+        // Already the 5th in Auckland.
+        invoice.issued = Utc.with_ymd_and_hms(2026, 3, 4, 23, 30, 0).unwrap();
+        let auckland = Calendar::from_config(&crate::calendar::CalendarConfig {
+            timezone: Some("Pacific/Auckland".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let text = render(
+            &invoice,
+            "{{project}} {{date}} {{unknown}}\n{{#lines}}{{label}} {{currency}}\n{{/lines}}",
+            InvoiceFormat::Text,
+            &auckland,
+        );
+        assert_eq!(
+            text,
+            "{{total_amount}} 2026-03-05 {{unknown}}\n{{currency}} USD\n"
+        );
+    }
+}
@@ -0,0 +1,502 @@
+use crate::{NetupiCore, PersistenceError, StartRequest, TimerEvent, TimerState, TimerStatus};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc;
+
+// The daemon speaks JSON-RPC 2.0 over a Unix domain socket, one JSON
+// object per line. Methods:
+//
+//   start      params: StartParams     result: TimerStatus
+//   stop       params: StopParams      result: TimerStatus before stopping, or null
+//   continue   params: ContinueParams  result: Continued
+//   pause                              result: true if a running timer was paused
+//   resume                             result: true if a paused timer was resumed
+//   status                             result: TimerStatus
+//   subscribe                          result: true, then "event" notifications
+//                                      with a TimerEvent as params
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// A request the daemon understood but rejected, e.g. an unknown preset.
+const CONFIG_ERROR: i64 = 1;
+
+/// A [`StartRequest`], optionally started at an earlier time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StartParams {
+    #[serde(flatten)]
+    request: StartRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    at: Option<DateTime<Utc>>,
+}
+
+/// When to stop the timer, if not now. May be omitted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StopParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    at: Option<DateTime<Utc>>,
+}
+
+/// Which earlier session to continue: 1 (the default) is the last one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ContinueParams {
+    #[serde(default = "default_continue")]
+    n: usize,
+}
+
+fn default_continue() -> usize {
+    1
+}
+
+/// The timer after a `continue`, and whether the last session was extended
+/// rather than a new one started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Continued {
+    extended: bool,
+    status: TimerStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl From<PersistenceError> for RpcError {
+    fn from(error: PersistenceError) -> Self {
+        match error {
+            PersistenceError::Config(message) => Self {
+                code: CONFIG_ERROR,
+                message,
+            },
+            error => Self {
+                code: INTERNAL_ERROR,
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl From<RpcError> for PersistenceError {
+    fn from(error: RpcError) -> Self {
+        match error.code {
+            CONFIG_ERROR => PersistenceError::Config(error.message),
+            _ => PersistenceError::Daemon(error.message),
+        }
+    }
+}
+
+/// Any message on the socket: a request, a response or a notification.
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Option<Value>,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// Serve `core` on the socket at `path` until `shutdown` completes, then
+/// save the running session (if any) and remove the socket.
+pub async fn serve(
+    core: NetupiCore,
+    path: &Path,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), PersistenceError> {
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(PersistenceError::Daemon(format!(
+                "a daemon is already listening on {}",
+                path.display()
+            )));
+        }
+        // Left over from a daemon that didn't shut down cleanly.
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+
+    tokio::pin!(shutdown);
+    let result = loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_connection(core.clone(), stream));
+                }
+                Err(e) => break Err(e.into()),
+            },
+            _ = &mut shutdown => break Ok(()),
+        }
+    };
+
+    let _ = std::fs::remove_file(path);
+    if core.timer().get_current_state().await.state != TimerState::Idle {
+        core.timer().stop_timer().await?;
+    }
+    result
+}
+
+async fn handle_connection(core: NetupiCore, stream: UnixStream) {
+    let (reader, mut writer) = stream.into_split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let line = format!("{}\n", message);
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Message>(&line) {
+            Ok(Message {
+                id,
+                method: Some(method),
+                params,
+                ..
+            }) => {
+                let result = dispatch(&core, &method, params.unwrap_or(Value::Null), &tx).await;
+                // Requests without an id are notifications and get no reply.
+                let Some(id) = id else {
+                    continue;
+                };
+                match result {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                    Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+                }
+            }
+            Ok(_) => error_response(PARSE_ERROR, "missing method".to_string()),
+            Err(e) => error_response(PARSE_ERROR, e.to_string()),
+        };
+        if tx.send(response).is_err() {
+            break;
+        }
+    }
+}
+
+fn error_response(code: i64, message: String) -> Value {
+    json!({"jsonrpc": "2.0", "id": null, "error": RpcError { code, message }})
+}
+
+async fn dispatch(
+    core: &NetupiCore,
+    method: &str,
+    params: Value,
+    tx: &mpsc::UnboundedSender<Value>,
+) -> Result<Value, RpcError> {
+    let result = match method {
+        "start" => {
+            let StartParams { request, at } = from_params(params)?;
+            match at {
+                Some(at) => core.start_at(request, at).await?,
+                None => core.start(request).await?,
+            }
+            to_value(core.status().await?)
+        }
+        "stop" => {
+            let StopParams { at } = from_params::<Option<_>>(params)?.unwrap_or_default();
+            to_value(core.stop_at(at.unwrap_or_else(Utc::now)).await?)
+        }
+        "continue" => {
+            let ContinueParams { n } = from_params(params)?;
+            let extended = core.continue_session(n).await?;
+            to_value(Continued {
+                extended,
+                status: core.status().await?,
+            })
+        }
+        "pause" => json!(core.timer().pause_timer().await),
+        "resume" => json!(core.timer().resume_timer().await),
+        "status" => to_value(core.status().await?),
+        "subscribe" => {
+            let mut events = core.timer().subscribe_events();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                while let Some(event) = events.recv().await {
+                    let notification =
+                        json!({"jsonrpc": "2.0", "method": "event", "params": event});
+                    if tx.send(notification).is_err() {
+                        break;
+                    }
+                }
+            });
+            json!(true)
+        }
+        _ => {
+            return Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("unknown method '{}'", method),
+            });
+        }
+    };
+    Ok(result)
+}
+
+fn from_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError {
+        code: INVALID_PARAMS,
+        message: e.to_string(),
+    })
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// A connection to a running daemon.
+pub struct Client {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    next_id: u64,
+    /// Events that arrived while waiting for a response.
+    events: VecDeque<TimerEvent>,
+}
+
+impl Client {
+    /// Connect to the daemon listening on `path`. Fails with an I/O error if
+    /// there is none.
+    pub async fn connect(path: &Path) -> Result<Self, PersistenceError> {
+        let (reader, writer) = UnixStream::connect(path).await?.into_split();
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+            next_id: 1,
+            events: VecDeque::new(),
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, PersistenceError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        self.writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await?;
+
+        loop {
+            let message = self.read_message().await?.ok_or_else(|| {
+                PersistenceError::Daemon("the daemon closed the connection".to_string())
+            })?;
+            if message.id != Some(json!(id)) {
+                continue;
+            }
+            if let Some(error) = message.error {
+                return Err(error.into());
+            }
+            return Ok(serde_json::from_value(
+                message.result.unwrap_or(Value::Null),
+            )?);
+        }
+    }
+
+    /// The next message from the daemon, after queueing it if it's an event.
+    async fn read_message(&mut self) -> Result<Option<Message>, PersistenceError> {
+        let Some(line) = self.lines.next_line().await? else {
+            return Ok(None);
+        };
+        let message: Message = serde_json::from_str(&line)?;
+        if message.method.as_deref() == Some("event")
+            && let Some(params) = &message.params
+        {
+            self.events
+                .push_back(serde_json::from_value(params.clone())?);
+        }
+        Ok(Some(message))
+    }
+
+    pub async fn start(&mut self, request: StartRequest) -> Result<TimerStatus, PersistenceError> {
+        self.call("start", to_value(request)).await
+    }
+
+    /// Like [`Client::start`], as if the timer had been started at `at`; see
+    /// [`NetupiCore::start_at`].
+    pub async fn start_at(
+        &mut self,
+        request: StartRequest,
+        at: DateTime<Utc>,
+    ) -> Result<TimerStatus, PersistenceError> {
+        let params = StartParams {
+            request,
+            at: Some(at),
+        };
+        self.call("start", to_value(params)).await
+    }
+
+    /// Stop and save the running timer. Returns its status just before it
+    /// stopped, or `None` if no timer was running.
+    pub async fn stop(&mut self) -> Result<Option<TimerStatus>, PersistenceError> {
+        self.call("stop", Value::Null).await
+    }
+
+    /// Like [`Client::stop`], as if the timer had been stopped at `at`.
+    pub async fn stop_at(
+        &mut self,
+        at: DateTime<Utc>,
+    ) -> Result<Option<TimerStatus>, PersistenceError> {
+        let params = StopParams { at: Some(at) };
+        self.call("stop", to_value(params)).await
+    }
+
+    /// Continue the `n`-th most recent session; see
+    /// [`NetupiCore::continue_session`]. Returns whether the last session
+    /// was extended, and the timer.
+    pub async fn continue_session(
+        &mut self,
+        n: usize,
+    ) -> Result<(bool, TimerStatus), PersistenceError> {
+        let continued: Continued = self.call("continue", json!({ "n": n })).await?;
+        Ok((continued.extended, continued.status))
+    }
+
+    pub async fn pause(&mut self) -> Result<bool, PersistenceError> {
+        self.call("pause", Value::Null).await
+    }
+
+    pub async fn resume(&mut self) -> Result<bool, PersistenceError> {
+        self.call("resume", Value::Null).await
+    }
+
+    pub async fn status(&mut self) -> Result<TimerStatus, PersistenceError> {
+        self.call("status", Value::Null).await
+    }
+
+    /// Ask for timer events, read with [`Client::next_event`].
+    pub async fn subscribe(&mut self) -> Result<(), PersistenceError> {
+        self.call::<bool>("subscribe", Value::Null).await?;
+        Ok(())
+    }
+
+    /// Wait for the next timer event. Returns `None` once the daemon
+    /// closes the connection.
+    pub async fn next_event(&mut self) -> Result<Option<TimerEvent>, PersistenceError> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+            if self.read_message().await?.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_core;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_daemon_serves_timer_commands() {
+        let (_dir, core) = test_core().await;
+        let path = core.persistence().socket_file();
+
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = {
+            let (core, path) = (core.clone(), path.clone());
+            tokio::spawn(async move {
+                serve(core, &path, async {
+                    let _ = shutdown_rx.await;
+                })
+                .await
+            })
+        };
+        let mut client = loop {
+            match Client::connect(&path).await {
+                Ok(client) => break client,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+        let mut listener = Client::connect(&path).await.unwrap();
+        listener.subscribe().await.unwrap();
+
+        let status = client
+            .start(StartRequest::Pomodoro {
+                project: Some("api".to_string()),
+                description: None,
+                preset: Some("deep".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(status.timer.state, TimerState::Running);
+        assert_eq!(status.timer.target_duration, Some(Duration::minutes(52)));
+        assert_eq!(status.session.unwrap().tags, ["api"]);
+        assert!(matches!(
+            listener.next_event().await.unwrap(),
+            Some(TimerEvent::Started)
+        ));
+
+        assert!(client.pause().await.unwrap());
+        assert!(!client.pause().await.unwrap());
+        assert_eq!(
+            client.status().await.unwrap().timer.state,
+            TimerState::Paused
+        );
+
+        let unknown = StartRequest::Pomodoro {
+            project: None,
+            description: None,
+            preset: Some("nap".to_string()),
+        };
+        assert!(matches!(
+            client.start(unknown).await,
+            Err(PersistenceError::Config(_))
+        ));
+        assert!(matches!(
+            client.call::<Value>("rewind", Value::Null).await,
+            Err(PersistenceError::Daemon(_))
+        ));
+
+        // The rejected start left the Pomodoro running.
+        let stopped = client.stop().await.unwrap().unwrap();
+        assert_eq!(stopped.timer.state, TimerState::Paused);
+        assert!(client.stop().await.unwrap().is_none());
+        assert_eq!(core.get_sessions().await.unwrap().len(), 1);
+
+        let last_end = core.get_sessions().await.unwrap()[0].end_time.unwrap();
+        assert!(matches!(
+            client
+                .start_at(StartRequest::Stopwatch, last_end - Duration::minutes(5))
+                .await,
+            Err(PersistenceError::Config(_))
+        ));
+        let status = client
+            .start_at(StartRequest::Stopwatch, last_end)
+            .await
+            .unwrap();
+        assert_eq!(status.session.unwrap().start_time, last_end);
+        assert!(matches!(
+            client.stop_at(last_end - Duration::minutes(5)).await,
+            Err(PersistenceError::Config(_))
+        ));
+        let (extended, status) = client.continue_session(1).await.unwrap();
+        assert!(!extended);
+        assert_eq!(status.session.unwrap().tags, ["api"]);
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        assert!(!path.exists());
+        assert_eq!(core.get_sessions().await.unwrap().len(), 3);
+    }
+}
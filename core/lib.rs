@@ -1,11 +1,40 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::{RwLock, mpsc};
 
+pub mod billing;
+pub mod budget;
+pub mod calendar;
+pub mod config;
+pub mod duration;
+pub mod goals;
+pub mod hooks;
+pub mod invoice;
+#[cfg(unix)]
+pub mod ipc;
+pub mod notify;
+pub mod period;
+pub mod rounding;
+pub mod stats;
+#[cfg(test)]
+mod testing;
+pub mod when;
+
+use budget::{Budget, BudgetStatus};
+use calendar::{Calendar, CalendarConfig};
+use config::{DisplayConfig, TimerPreset};
+use goals::{GoalProgress, Goals};
+use hooks::HooksConfig;
+use invoice::{Invoice, InvoiceFormat, InvoiceOptions};
+use notify::NotificationConfig;
+use period::{Period, PeriodKind, PeriodSummary};
+use rounding::Rounding;
+use stats::PomodoroStats;
+
 // =============================================================================
 // TYPES & ENUMS (Future module: types.rs)
 // =============================================================================
@@ -19,6 +48,14 @@ pub enum TimerType {
     CustomTimer,
 }
 
+impl TimerType {
+    /// Whether time of this type counts as work on a project (breaks and
+    /// custom countdowns don't).
+    pub fn is_work(&self) -> bool {
+        matches!(self, TimerType::Stopwatch | TimerType::PomodoroWork)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TimerState {
     Idle,
@@ -27,14 +64,25 @@ pub enum TimerState {
     Completed,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TimerEvent {
     Started,
     Paused,
     Resumed,
     Stopped,
-    Completed,
+    /// A timer with a target duration (a Pomodoro phase or a custom
+    /// countdown) reached it.
+    Completed {
+        timer_type: TimerType,
+        label: Option<String>,
+    },
     Tick(Duration),
+    /// A running session pushed its project past `percent` of its budget.
+    BudgetThreshold {
+        project: String,
+        percent: u8,
+    },
 }
 
 // =============================================================================
@@ -51,6 +99,61 @@ pub struct WorkSession {
     pub paused_duration: Duration,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    /// Overrides the project's billable flag for this session only.
+    #[serde(default)]
+    pub billable: Option<bool>,
+    /// Overrides the project's hourly rate for this session only.
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    /// Number of the invoice this session was billed on, if any.
+    #[serde(default)]
+    pub invoice: Option<String>,
+    /// How many times the session was paused.
+    #[serde(default)]
+    pub pause_count: u32,
+    /// Length the session's timer was set to, for Pomodoros and countdowns.
+    #[serde(default)]
+    pub target_duration: Option<Duration>,
+}
+
+impl WorkSession {
+    /// The project this session's time counts towards: its first tag, for
+    /// stopwatch and Pomodoro work sessions.
+    pub fn project(&self) -> Option<&str> {
+        if !self.session_type.is_work() {
+            return None;
+        }
+        self.tags.first().map(String::as_str)
+    }
+}
+
+/// Per-project billing settings, stored in `projects.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSettings {
+    #[serde(default)]
+    pub billable: bool,
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Overrides the global rounding rule for this project.
+    #[serde(default)]
+    pub rounding: Option<Rounding>,
+    #[serde(default)]
+    pub budget: Option<Budget>,
+}
+
+/// Aggregated time and earnings for one project over some set of sessions.
+/// `total` is the exact tracked time; `rounded` and the billing figures have
+/// the project's rounding rule applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub name: String,
+    pub total: Duration,
+    pub rounded: Duration,
+    pub billable: Duration,
+    pub earnings: f64,
+    pub currency: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +164,32 @@ pub struct TimerConfig {
     pub long_break_interval: u32,
     pub auto_start_breaks: bool,
     pub auto_start_work: bool,
+    /// Rounding applied to reported and invoiced time.
+    #[serde(default)]
+    pub rounding: Rounding,
+    #[serde(default)]
+    pub goals: Goals,
+    /// Time zone, day start and first day of the week used to group time.
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    /// Days covered by reports and stats when no range is given.
+    #[serde(default = "default_report_days")]
+    pub report_days: u32,
+    /// How long after the last session ended `continue` extends it instead
+    /// of starting a new one.
+    #[serde(default)]
+    pub resume_last: Option<Duration>,
+    #[serde(default)]
+    pub display: DisplayConfig,
+    /// Named Pomodoro rhythms, selectable per cycle or as the default.
+    #[serde(default = "config::default_presets")]
+    pub presets: BTreeMap<String, TimerPreset>,
+    /// Commands run on timer events.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Which completions and alerts notify, and how.
+    #[serde(default)]
+    pub notifications: NotificationConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +203,60 @@ pub struct TimerData {
     pub pause_start: Option<DateTime<Utc>>,
 }
 
+/// The timer a `start` request starts. A timer that is already running is
+/// stopped and saved first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "timer", rename_all = "snake_case")]
+pub enum StartRequest {
+    Pomodoro {
+        #[serde(default)]
+        project: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        preset: Option<String>,
+    },
+    ShortBreak,
+    LongBreak,
+    Stopwatch,
+    /// A stopwatch on a project, optionally overriding its billing.
+    Project {
+        project: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        billable: Option<bool>,
+        #[serde(default)]
+        rate: Option<f64>,
+    },
+    Countdown {
+        duration: Duration,
+        #[serde(default)]
+        label: Option<String>,
+    },
+}
+
+/// The running timer, its session and the budget of its project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerStatus {
+    pub timer: TimerData,
+    pub session: Option<WorkSession>,
+    /// Preset of the current Pomodoro cycle, if one was chosen for it.
+    pub cycle_preset: Option<String>,
+    pub budget: Option<BudgetStatus>,
+}
+
+/// The running timer as recorded in `active.json`, so other processes (e.g.
+/// status bars) can show it without talking to the one running it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveTimer {
+    /// Process running the timer.
+    pub pid: u32,
+    pub timer: TimerData,
+    pub session: Option<WorkSession>,
+    pub cycle_preset: Option<String>,
+}
+
 // =============================================================================
 // ERROR TYPES (Future module: errors.rs)
 // =============================================================================
@@ -86,6 +269,8 @@ pub enum PersistenceError {
     Serialization(#[from] serde_json::Error),
     #[error("Configuration error: {0}")]
     Config(String),
+    #[error("Daemon error: {0}")]
+    Daemon(String),
 }
 
 // =============================================================================
@@ -93,8 +278,12 @@ pub enum PersistenceError {
 // =============================================================================
 
 pub struct PersistenceManager {
+    data_dir: PathBuf,
     sessions_file: PathBuf,
     config_file: PathBuf,
+    /// `config.json` from older versions, read once if `config.toml` is missing.
+    legacy_config_file: PathBuf,
+    projects_file: PathBuf,
 }
 
 impl PersistenceManager {
@@ -103,25 +292,70 @@ impl PersistenceManager {
             .unwrap_or_else(|| PathBuf::from("."))
             .join("netupi23");
 
+        Self::with_data_dir(data_dir)
+    }
+
+    /// Store all data under `data_dir` instead of the platform data directory.
+    pub fn with_data_dir(data_dir: PathBuf) -> Result<Self, PersistenceError> {
         std::fs::create_dir_all(&data_dir)?;
 
         Ok(Self {
             sessions_file: data_dir.join("sessions.json"),
-            config_file: data_dir.join("config.json"),
+            config_file: data_dir.join("config.toml"),
+            legacy_config_file: data_dir.join("config.json"),
+            projects_file: data_dir.join("projects.json"),
+            data_dir,
         })
     }
 
     pub async fn save_session(&self, session: &WorkSession) -> Result<(), PersistenceError> {
-        let mut sessions = self.load_sessions().await.unwrap_or_default();
+        self.update_sessions(|sessions| {
+            if let Some(pos) = sessions.iter().position(|s| s.id == session.id) {
+                sessions[pos] = session.clone();
+            } else {
+                sessions.push(session.clone());
+            }
+        })
+        .await
+    }
 
-        if let Some(pos) = sessions.iter().position(|s| s.id == session.id) {
-            sessions[pos] = session.clone();
-        } else {
-            sessions.push(session.clone());
-        }
+    /// Change the saved sessions with `update`. Other `netupi` processes
+    /// (the daemon, a CLI command) wait until the result is written, so
+    /// neither overwrites the other's changes.
+    pub async fn update_sessions<R>(
+        &self,
+        update: impl FnOnce(&mut Vec<WorkSession>) -> R,
+    ) -> Result<R, PersistenceError> {
+        let _lock = self.lock_sessions().await?;
+        let mut sessions = self.load_sessions().await?;
+        let result = update(&mut sessions);
+        self.write_sessions(&sessions).await?;
+        Ok(result)
+    }
+
+    /// Take the lock on `sessions.lock`, waiting for other processes to
+    /// release it. It's held until the returned file is dropped.
+    async fn lock_sessions(&self) -> Result<std::fs::File, PersistenceError> {
+        let path = self.data_dir.join("sessions.lock");
+        let file = tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(path)?;
+            file.lock()?;
+            Ok::<_, std::io::Error>(file)
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        Ok(file)
+    }
 
-        let json = serde_json::to_string_pretty(&sessions)?;
-        tokio::fs::write(&self.sessions_file, json).await?;
+    /// Replace `sessions.json` in one step, so readers never see half a file.
+    async fn write_sessions(&self, sessions: &[WorkSession]) -> Result<(), PersistenceError> {
+        let temp = self.data_dir.join("sessions.json.tmp");
+        tokio::fs::write(&temp, serde_json::to_string_pretty(sessions)?).await?;
+        tokio::fs::rename(&temp, &self.sessions_file).await?;
         Ok(())
     }
 
@@ -135,22 +369,140 @@ impl PersistenceManager {
         Ok(sessions) // Fixed: was Ok(())
     }
 
+    pub async fn save_sessions(&self, sessions: &[WorkSession]) -> Result<(), PersistenceError> {
+        let _lock = self.lock_sessions().await?;
+        self.write_sessions(sessions).await
+    }
+
+    /// Path of the socket `netupi daemon` listens on.
+    pub fn socket_file(&self) -> PathBuf {
+        self.data_dir.join("daemon.sock")
+    }
+
+    /// Path of the file describing the running timer, if any.
+    pub fn active_timer_file(&self) -> PathBuf {
+        self.data_dir.join("active.json")
+    }
+
+    pub async fn save_active_timer(&self, active: &ActiveTimer) -> Result<(), PersistenceError> {
+        // Status bars read this every second; never let them see half a file.
+        let temp = self.data_dir.join("active.json.tmp");
+        tokio::fs::write(&temp, serde_json::to_string(active)?).await?;
+        tokio::fs::rename(&temp, self.active_timer_file()).await?;
+        Ok(())
+    }
+
+    /// Remove `active.json` if this process wrote it or the process that
+    /// did is gone; a timer another live process is running stays visible.
+    pub async fn clear_active_timer(&self) -> Result<(), PersistenceError> {
+        let content = match tokio::fs::read_to_string(self.active_timer_file()).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if let Ok(active) = serde_json::from_str::<ActiveTimer>(&content)
+            && active.pid != std::process::id()
+            && process_exists(active.pid)
+        {
+            return Ok(());
+        }
+        match tokio::fs::remove_file(self.active_timer_file()).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The timer some `netupi` process is running, if any. A file left
+    /// behind by a process that was killed is ignored.
+    pub async fn load_active_timer(&self) -> Result<Option<ActiveTimer>, PersistenceError> {
+        let content = match tokio::fs::read_to_string(self.active_timer_file()).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let active: ActiveTimer = serde_json::from_str(&content)?;
+        Ok(process_exists(active.pid).then_some(active))
+    }
+
+    /// Where failing hooks are logged.
+    pub fn hooks_log_file(&self) -> PathBuf {
+        self.data_dir.join("hooks.log")
+    }
+
+    /// Path of the user-editable config file.
+    pub fn config_file(&self) -> &std::path::Path {
+        &self.config_file
+    }
+
+    /// When `config.toml` was last changed, if it exists.
+    pub async fn config_modified(&self) -> Option<std::time::SystemTime> {
+        let metadata = tokio::fs::metadata(&self.config_file).await.ok()?;
+        metadata.modified().ok()
+    }
+
     pub async fn save_config(&self, config: &TimerConfig) -> Result<(), PersistenceError> {
-        let json = serde_json::to_string_pretty(config)?;
-        tokio::fs::write(&self.config_file, json).await?;
+        tokio::fs::write(&self.config_file, config.to_toml()).await?;
         Ok(())
     }
 
+    /// Load `config.toml`, creating it from the legacy `config.json` (or
+    /// the defaults) on first use.
     pub async fn load_config(&self) -> Result<TimerConfig, PersistenceError> {
         if !self.config_file.exists() {
-            let default_config = TimerConfig::default();
-            self.save_config(&default_config).await?;
-            return Ok(default_config);
+            let config = if self.legacy_config_file.exists() {
+                let content = tokio::fs::read_to_string(&self.legacy_config_file).await?;
+                serde_json::from_str(&content)?
+            } else {
+                TimerConfig::default()
+            };
+            self.save_config(&config).await?;
+            return Ok(config);
         }
 
         let content = tokio::fs::read_to_string(&self.config_file).await?;
-        let config: TimerConfig = serde_json::from_str(&content)?;
-        Ok(config)
+        TimerConfig::from_toml(&content).map_err(|e| match e {
+            PersistenceError::Config(message) => {
+                PersistenceError::Config(format!("{}: {}", self.config_file.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    pub async fn load_project_settings(
+        &self,
+    ) -> Result<HashMap<String, ProjectSettings>, PersistenceError> {
+        if !self.projects_file.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = tokio::fs::read_to_string(&self.projects_file).await?;
+        let settings: HashMap<String, ProjectSettings> = serde_json::from_str(&content)?;
+        Ok(settings)
+    }
+
+    pub async fn save_project_settings(
+        &self,
+        settings: &HashMap<String, ProjectSettings>,
+    ) -> Result<(), PersistenceError> {
+        let json = serde_json::to_string_pretty(settings)?;
+        tokio::fs::write(&self.projects_file, json).await?;
+        Ok(())
+    }
+
+    /// Load the user-editable invoice template for `format`, writing the
+    /// built-in default next to `config.toml` on first use.
+    pub async fn load_invoice_template(
+        &self,
+        format: InvoiceFormat,
+    ) -> Result<String, PersistenceError> {
+        let path = self.data_dir.join(format.template_file_name());
+        if !path.exists() {
+            let template = format.default_template();
+            tokio::fs::write(&path, template).await?;
+            return Ok(template.to_string());
+        }
+
+        Ok(tokio::fs::read_to_string(&path).await?)
     }
 }
 
@@ -158,53 +510,217 @@ impl PersistenceManager {
 // TIMER ENGINE (Future module: timer.rs)
 // =============================================================================
 
+/// Fan-out of timer events to every subscriber; closed receivers are
+/// dropped on the next send.
+#[derive(Clone, Default)]
+struct EventSenders(Arc<std::sync::Mutex<Vec<mpsc::UnboundedSender<TimerEvent>>>>);
+
+impl EventSenders {
+    fn subscribe(&self) -> mpsc::UnboundedReceiver<TimerEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if let Ok(mut senders) = self.0.lock() {
+            senders.push(tx);
+        }
+        rx
+    }
+
+    fn send(&self, event: TimerEvent) {
+        if let Ok(mut senders) = self.0.lock() {
+            senders.retain(|sender| sender.send(event.clone()).is_ok());
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct TimerEngine {
     data: Arc<RwLock<TimerData>>,
     config: Arc<RwLock<TimerConfig>>,
+    /// Modification time of `config.toml` when `config` was read, to pick
+    /// up changes made by other processes or by hand.
+    config_modified: Arc<RwLock<Option<std::time::SystemTime>>>,
     persistence: Arc<PersistenceManager>,
-    event_sender: Option<mpsc::UnboundedSender<TimerEvent>>,
+    events: EventSenders,
     current_session: Arc<RwLock<Option<WorkSession>>>,
+    /// Project of the current Pomodoro cycle, carried over to its breaks and
+    /// to following work sessions started without a project.
+    cycle_project: Arc<RwLock<Option<String>>>,
+    /// Preset chosen for the current Pomodoro cycle, overriding the default
+    /// timer lengths until a non-Pomodoro timer starts.
+    cycle_preset: Arc<RwLock<Option<String>>>,
 }
 
 impl TimerEngine {
     pub async fn new(persistence: Arc<PersistenceManager>) -> Result<Self, PersistenceError> {
         let config = persistence.load_config().await?;
+        let config_modified = persistence.config_modified().await;
 
         Ok(Self {
             data: Arc::new(RwLock::new(TimerData::default())),
             config: Arc::new(RwLock::new(config)),
+            config_modified: Arc::new(RwLock::new(config_modified)),
             persistence,
-            event_sender: None,
+            events: EventSenders::default(),
             current_session: Arc::new(RwLock::new(None)),
+            cycle_project: Arc::new(RwLock::new(None)),
+            cycle_preset: Arc::new(RwLock::new(None)),
         })
     }
 
-    pub fn subscribe_events(&mut self) -> mpsc::UnboundedReceiver<TimerEvent> {
-        let (tx, rx) = mpsc::unbounded_channel();
-        self.event_sender = Some(tx);
-        rx
+    /// The configuration, read again if `config.toml` changed since it was
+    /// last read. Until a broken edit is fixed, the last good one is kept.
+    pub async fn get_config(&self) -> TimerConfig {
+        let modified = self.persistence.config_modified().await;
+        let mut loaded = self.config_modified.write().await;
+        if modified != *loaded
+            && let Ok(config) = self.persistence.load_config().await
+        {
+            *self.config.write().await = config;
+            *loaded = modified;
+        }
+        self.config.read().await.clone()
+    }
+
+    /// The calendar used to split time into days and weeks.
+    pub async fn calendar(&self) -> Result<Calendar, PersistenceError> {
+        Calendar::from_config(&self.get_config().await.calendar)
+    }
+
+    /// Replace the configuration and save it.
+    pub async fn set_config(&self, config: TimerConfig) -> Result<(), PersistenceError> {
+        Calendar::from_config(&config.calendar)?;
+        let mut loaded = self.config_modified.write().await;
+        self.persistence.save_config(&config).await?;
+        *self.config.write().await = config;
+        *loaded = self.persistence.config_modified().await;
+        Ok(())
+    }
+
+    pub fn subscribe_events(&self) -> mpsc::UnboundedReceiver<TimerEvent> {
+        self.events.subscribe()
+    }
+
+    /// The project of the current Pomodoro cycle, if any.
+    pub async fn cycle_project(&self) -> Option<String> {
+        self.cycle_project.read().await.clone()
+    }
+
+    /// The preset of the current Pomodoro cycle, if one was chosen for it.
+    pub async fn cycle_preset(&self) -> Option<String> {
+        self.cycle_preset.read().await.clone()
+    }
+
+    /// Use the named preset's lengths for the Pomodoro cycle, starting with
+    /// the next work session or break; `None` goes back to the defaults.
+    pub async fn set_cycle_preset(&self, name: Option<String>) -> Result<(), PersistenceError> {
+        if let Some(name) = &name {
+            self.get_config().await.preset(name)?;
+        }
+        *self.cycle_preset.write().await = name;
+        Ok(())
+    }
+
+    /// The lengths, long break interval and auto-start flags in effect: the
+    /// cycle's preset if one was chosen, the configured ones otherwise.
+    pub async fn rhythm(&self) -> TimerPreset {
+        let config = self.get_config().await;
+        self.cycle_preset
+            .read()
+            .await
+            .as_ref()
+            .and_then(|name| config.presets.get(name).cloned())
+            .unwrap_or_else(|| config.timer_preset())
+    }
+
+    /// The session being timed, if any.
+    pub async fn current_session(&self) -> Option<WorkSession> {
+        self.current_session.read().await.clone()
+    }
+
+    /// Record the timer in `active.json` for other processes. A failed
+    /// write only affects what they show, so it's ignored.
+    async fn save_active(&self, data: &TimerData) {
+        let active = ActiveTimer {
+            pid: std::process::id(),
+            timer: data.clone(),
+            session: self.current_session().await,
+            cycle_preset: self.cycle_preset().await,
+        };
+        let _ = self.persistence.save_active_timer(&active).await;
     }
 
     pub async fn start_timer(&self, timer_type: TimerType) -> Result<(), PersistenceError> {
         self.start_work_session(timer_type, None, None).await
     }
 
+    /// Start a countdown of `duration`. It stops and saves its session when
+    /// it reaches zero.
+    pub async fn start_countdown(
+        &self,
+        duration: Duration,
+        label: Option<String>,
+    ) -> Result<(), PersistenceError> {
+        if duration <= Duration::zero() {
+            return Err(PersistenceError::Config(
+                "timer duration must be positive".to_string(),
+            ));
+        }
+        self.continue_cycle(TimerType::CustomTimer, None).await;
+        self.start_session(TimerType::CustomTimer, Some(duration), None, label)
+            .await
+    }
+
     pub async fn start_work_session(
         &self,
         timer_type: TimerType,
         project_name: Option<String>,
         description: Option<String>,
     ) -> Result<(), PersistenceError> {
-        let mut data = self.data.write().await;
-        let config = self.config.read().await;
-
+        let project_name = self.continue_cycle(timer_type, project_name).await;
+        let rhythm = self.rhythm().await;
         let target_duration = match timer_type {
-            TimerType::PomodoroWork => Some(config.pomodoro_work_duration),
-            TimerType::PomodoroShortBreak => Some(config.pomodoro_short_break_duration),
-            TimerType::PomodoroLongBreak => Some(config.pomodoro_long_break_duration),
+            TimerType::PomodoroWork => Some(rhythm.work),
+            TimerType::PomodoroShortBreak => Some(rhythm.short_break),
+            TimerType::PomodoroLongBreak => Some(rhythm.long_break),
             _ => None,
         };
+        self.start_session(timer_type, target_duration, project_name, description)
+            .await
+    }
 
+    /// Carry the cycle's project over to a Pomodoro session started without
+    /// one. Any other kind of timer ends the cycle, and its preset with it.
+    async fn continue_cycle(
+        &self,
+        timer_type: TimerType,
+        project_name: Option<String>,
+    ) -> Option<String> {
+        let mut cycle = self.cycle_project.write().await;
+        match timer_type {
+            TimerType::PomodoroWork => {
+                if project_name.is_some() {
+                    *cycle = project_name.clone();
+                }
+                project_name.or_else(|| cycle.clone())
+            }
+            TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak => {
+                project_name.or_else(|| cycle.clone())
+            }
+            _ => {
+                *cycle = None;
+                *self.cycle_preset.write().await = None;
+                project_name
+            }
+        }
+    }
+
+    async fn start_session(
+        &self,
+        timer_type: TimerType,
+        target_duration: Option<Duration>,
+        project_name: Option<String>,
+        description: Option<String>,
+    ) -> Result<(), PersistenceError> {
+        let mut data = self.data.write().await;
         *data = TimerData {
             timer_type,
             state: TimerState::Running,
@@ -217,7 +733,7 @@ impl TimerEngine {
 
         // Create session
         let mut tags = Vec::new();
-        if let Some(project) = project_name {
+        if let Some(project) = project_name.clone() {
             tags.push(project);
         }
 
@@ -230,54 +746,346 @@ impl TimerEngine {
             paused_duration: Duration::zero(),
             description,
             tags,
+            billable: None,
+            hourly_rate: None,
+            invoice: None,
+            pause_count: 0,
+            target_duration,
         };
 
+        let session_id = session.id;
         *self.current_session.write().await = Some(session);
+        self.save_active(&data).await;
 
-        if let Some(sender) = &self.event_sender {
-            let _ = sender.send(TimerEvent::Started);
+        self.events.send(TimerEvent::Started);
+        if let Some(project) = project_name
+            && timer_type.is_work()
+        {
+            self.spawn_session_monitor(session_id, project);
+        }
+        if target_duration.is_some() {
+            self.spawn_countdown_monitor(session_id);
         }
 
         Ok(())
     }
 
+    /// Emit `Completed` once the session's timer reaches its target
+    /// duration. Custom countdowns are then stopped and saved, and so are
+    /// Pomodoro phases followed by another one with `auto_start_breaks` or
+    /// `auto_start_work`; other phases keep running until stopped. The task
+    /// ends once the session is stopped or replaced.
+    fn spawn_countdown_monitor(&self, session_id: uuid::Uuid) {
+        let engine = self.clone();
+        let data = self.data.clone();
+        let current_session = self.current_session.clone();
+        let persistence = self.persistence.clone();
+        let events = self.events.clone();
+
+        tokio::spawn(async move {
+            loop {
+                // Wake up at the expected end, but at least every second to
+                // notice pauses and stops.
+                let remaining = data.read().await.remaining_at(Utc::now());
+                let wait = remaining
+                    .and_then(|r| r.to_std().ok())
+                    .unwrap_or_default()
+                    .min(std::time::Duration::from_secs(1));
+                tokio::time::sleep(wait).await;
+
+                let mut data = data.write().await;
+                let Some(session) = current_session.read().await.clone() else {
+                    break;
+                };
+                if session.id != session_id {
+                    break;
+                }
+                if data.state != TimerState::Running
+                    || data.remaining_at(Utc::now()) != Some(Duration::zero())
+                {
+                    continue;
+                }
+
+                let next = engine.auto_start_after(session.session_type).await;
+                if session.session_type == TimerType::CustomTimer || next.is_some() {
+                    // Nobody is waiting on the result; the countdown is
+                    // still reported as completed.
+                    let _ =
+                        finish_session(&mut data, &current_session, &persistence, Utc::now()).await;
+                    events.send(TimerEvent::Stopped);
+                }
+                events.send(TimerEvent::Completed {
+                    timer_type: session.session_type,
+                    label: session.description,
+                });
+                drop(data);
+                if let Some(next) = next {
+                    let _ = engine.start_work_session(next, None, None).await;
+                }
+                break;
+            }
+        });
+    }
+
+    /// The Pomodoro phase to start on its own once a `timer_type` phase
+    /// completes, if auto-start is on for it: a break after work, long once
+    /// `long_break_interval` Pomodoros are done, and work after a break.
+    async fn auto_start_after(&self, timer_type: TimerType) -> Option<TimerType> {
+        let config = self.get_config().await;
+        let rhythm = self.rhythm().await;
+        match timer_type {
+            TimerType::PomodoroWork if rhythm.auto_start_breaks => {
+                let calendar = Calendar::from_config(&config.calendar).unwrap_or_default();
+                let today = calendar.day_start(calendar.today());
+                let sessions = self.persistence.load_sessions().await.unwrap_or_default();
+                let interval = rhythm.long_break_interval;
+                // The Pomodoro that just ended isn't saved yet.
+                let done = goals::cycle_position(&sessions, &config, interval, today) + 1;
+                Some(if done >= interval {
+                    TimerType::PomodoroLongBreak
+                } else {
+                    TimerType::PomodoroShortBreak
+                })
+            }
+            TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak
+                if rhythm.auto_start_work =>
+            {
+                Some(TimerType::PomodoroWork)
+            }
+            _ => None,
+        }
+    }
+
+    /// Watch a running project session in the background and emit
+    /// `BudgetThreshold` events as it pushes the project past its budget
+    /// alert levels. The task ends once the session is stopped or replaced.
+    fn spawn_session_monitor(&self, session_id: uuid::Uuid, project: String) {
+        let data = self.data.clone();
+        let current_session = self.current_session.clone();
+        let persistence = self.persistence.clone();
+        let events = self.events.clone();
+        let config = self.config.clone();
+
+        tokio::spawn(async move {
+            let calendar = Calendar::from_config(&config.read().await.calendar).unwrap_or_default();
+            let budget = persistence
+                .load_project_settings()
+                .await
+                .ok()
+                .and_then(|mut settings| settings.remove(&project))
+                .and_then(|settings| settings.budget);
+            let Some(budget) = budget else {
+                return;
+            };
+            // A resumed session is still saved; its time is in `elapsed`.
+            let mut sessions = persistence.load_sessions().await.unwrap_or_default();
+            sessions.retain(|s| s.id != session_id);
+            let before =
+                budget::consumed(&sessions, &project, budget.period, Utc::now(), &calendar);
+            let mut last = before;
+
+            loop {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                if current_session.read().await.as_ref().map(|s| s.id) != Some(session_id) {
+                    break;
+                }
+
+                let consumed = before + data.read().await.elapsed_at(Utc::now());
+                for percent in budget::crossed_thresholds(&budget, last, consumed) {
+                    events.send(TimerEvent::BudgetThreshold {
+                        project: project.clone(),
+                        percent,
+                    });
+                }
+                last = consumed;
+            }
+        });
+    }
+
     pub async fn get_current_state(&self) -> TimerData {
         let mut data = self.data.write().await;
 
         // Update elapsed time if timer is running
         if data.state == TimerState::Running {
-            if let Some(start_time) = data.start_time {
-                let now = Utc::now();
-                data.elapsed = now.signed_duration_since(start_time) - data.paused_time;
-            }
+            data.elapsed = data.elapsed_at(Utc::now());
         }
 
         data.clone()
     }
 
-    pub async fn stop_timer(&self) -> Result<(), PersistenceError> {
+    /// Pause the running timer. Returns `false` if no timer is running.
+    pub async fn pause_timer(&self) -> bool {
         let mut data = self.data.write().await;
-        data.state = TimerState::Idle;
+        if data.state != TimerState::Running {
+            return false;
+        }
+
+        let now = Utc::now();
+        data.elapsed = data.elapsed_at(now);
+        data.state = TimerState::Paused;
+        data.pause_start = Some(now);
+        if let Some(session) = self.current_session.write().await.as_mut() {
+            session.pause_count += 1;
+        }
+        self.save_active(&data).await;
 
-        // Save current session if exists
-        if let Some(session) = self.current_session.write().await.take() {
-            let mut final_session = session;
-            final_session.end_time = Some(Utc::now());
-            final_session.duration = data.elapsed;
-            final_session.paused_duration = data.paused_time;
+        self.events.send(TimerEvent::Paused);
+        true
+    }
 
-            self.persistence.save_session(&final_session).await?;
+    /// Resume a paused timer. Returns `false` if the timer isn't paused.
+    pub async fn resume_timer(&self) -> bool {
+        let mut data = self.data.write().await;
+        if data.state != TimerState::Paused {
+            return false;
+        }
+
+        if let Some(pause_start) = data.pause_start.take() {
+            data.paused_time += Utc::now() - pause_start;
         }
+        data.state = TimerState::Running;
+        self.save_active(&data).await;
+
+        self.events.send(TimerEvent::Resumed);
+        true
+    }
+
+    pub async fn stop_timer(&self) -> Result<(), PersistenceError> {
+        self.stop_timer_at(Utc::now()).await
+    }
 
-        // Reset timer data
-        *data = TimerData::default();
+    /// Stop and save the timer as if it had been stopped at `end`, which
+    /// must lie between its start and now.
+    pub async fn stop_timer_at(&self, end: DateTime<Utc>) -> Result<(), PersistenceError> {
+        let mut data = self.data.write().await;
+        if end > Utc::now() {
+            return Err(PersistenceError::Config(
+                "can't stop a timer in the future".to_string(),
+            ));
+        }
+        if data.start_time.is_some_and(|start| end < start) {
+            return Err(PersistenceError::Config(
+                "can't stop a timer before it started".to_string(),
+            ));
+        }
+        finish_session(&mut data, &self.current_session, &self.persistence, end).await?;
+
+        self.events.send(TimerEvent::Stopped);
+
+        Ok(())
+    }
 
-        if let Some(sender) = &self.event_sender {
-            let _ = sender.send(TimerEvent::Stopped);
+    /// Move the start of the running timer back to `start`, for a timer
+    /// started late. The caller checks that `start` lies in the past and
+    /// after the last saved session. Returns `false` if no timer is running.
+    pub async fn backdate(&self, start: DateTime<Utc>) -> bool {
+        let mut data = self.data.write().await;
+        match self.current_session.write().await.as_mut() {
+            Some(session) => session.start_time = start,
+            None => return false,
         }
+        data.start_time = Some(start);
+        self.save_active(&data).await;
+        true
+    }
+
+    /// Keep timing a saved session, with the time since it ended counted as
+    /// a pause. The saved copy stays in the log until the timer stops and
+    /// replaces it, so a process killed in between loses nothing.
+    pub async fn resume_session(&self, session: WorkSession) -> Result<(), PersistenceError> {
+        let project = self
+            .continue_cycle(session.session_type, session.project().map(str::to_string))
+            .await;
+        let now = Utc::now();
+        let mut data = self.data.write().await;
+        *data = TimerData {
+            timer_type: session.session_type,
+            state: TimerState::Running,
+            target_duration: session.target_duration,
+            elapsed: session.duration,
+            paused_time: now - session.start_time - session.duration,
+            start_time: Some(session.start_time),
+            pause_start: None,
+        };
+        let session_id = session.id;
+        let is_work = session.session_type.is_work();
+        *self.current_session.write().await = Some(WorkSession {
+            end_time: None,
+            paused_duration: data.paused_time,
+            pause_count: session.pause_count + 1,
+            ..session
+        });
+        self.save_active(&data).await;
 
+        self.events.send(TimerEvent::Resumed);
+        if let Some(project) = project
+            && is_work
+        {
+            self.spawn_session_monitor(session_id, project);
+        }
         Ok(())
     }
+
+    /// Override the billable flag and/or hourly rate of the running session.
+    /// Returns `false` if no session is in progress.
+    pub async fn set_billing_override(
+        &self,
+        billable: Option<bool>,
+        hourly_rate: Option<f64>,
+    ) -> bool {
+        match self.current_session.write().await.as_mut() {
+            Some(session) => {
+                if billable.is_some() {
+                    session.billable = billable;
+                }
+                if hourly_rate.is_some() {
+                    session.hourly_rate = hourly_rate;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Save the current session, if any, with the time worked until `end` and
+/// reset the timer.
+async fn finish_session(
+    data: &mut TimerData,
+    current_session: &RwLock<Option<WorkSession>>,
+    persistence: &PersistenceManager,
+    end: DateTime<Utc>,
+) -> Result<(), PersistenceError> {
+    data.elapsed = data.elapsed_until(end);
+    data.paused_time = data.paused_until(end);
+    data.pause_start = None;
+    data.state = TimerState::Idle;
+
+    // Save current session if exists
+    if let Some(session) = current_session.write().await.take() {
+        let mut final_session = session;
+        final_session.end_time = Some(end);
+        final_session.duration = data.elapsed;
+        final_session.paused_duration = data.paused_time;
+
+        persistence.save_session(&final_session).await?;
+    }
+    persistence.clear_active_timer().await?;
+
+    // Reset timer data
+    *data = TimerData::default();
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn process_exists(pid: u32) -> bool {
+    std::path::Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Without a cheap way to check, trust the file.
+#[cfg(not(target_os = "linux"))]
+fn process_exists(_pid: u32) -> bool {
+    true
 }
 
 // =============================================================================
@@ -293,10 +1101,66 @@ impl Default for TimerConfig {
             long_break_interval: 4,
             auto_start_breaks: false,
             auto_start_work: false,
+            rounding: Rounding::default(),
+            goals: Goals::default(),
+            calendar: CalendarConfig::default(),
+            report_days: default_report_days(),
+            resume_last: None,
+            display: DisplayConfig::default(),
+            presets: config::default_presets(),
+            hooks: HooksConfig::default(),
+            notifications: NotificationConfig::default(),
         }
     }
 }
 
+fn default_report_days() -> u32 {
+    7
+}
+
+impl TimerData {
+    /// Elapsed time as of `now`, excluding paused time.
+    pub fn elapsed_at(&self, now: DateTime<Utc>) -> Duration {
+        match (self.state, self.start_time) {
+            (TimerState::Running, Some(start_time)) => {
+                now.signed_duration_since(start_time) - self.paused_time
+            }
+            _ => self.elapsed,
+        }
+    }
+
+    /// Time worked if the timer stops at `end`, excluding paused time.
+    pub fn elapsed_until(&self, end: DateTime<Utc>) -> Duration {
+        match self.start_time {
+            Some(start_time) => (end - start_time - self.paused_until(end)).max(Duration::zero()),
+            None => self.elapsed,
+        }
+    }
+
+    /// Time spent paused if the timer stops at `end`. A pause that began
+    /// after `end` doesn't count.
+    fn paused_until(&self, end: DateTime<Utc>) -> Duration {
+        let pause = self
+            .pause_start
+            .map_or(Duration::zero(), |pause_start| end - pause_start);
+        self.paused_time + pause.max(Duration::zero())
+    }
+
+    /// Time left until the target duration as of `now`, or `None` for
+    /// timers without one.
+    pub fn remaining_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.target_duration
+            .map(|target| (target - self.elapsed_at(now)).max(Duration::zero()))
+    }
+
+    /// Time left until the target duration, as of the last update of
+    /// `elapsed`.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.target_duration
+            .map(|target| (target - self.elapsed).max(Duration::zero()))
+    }
+}
+
 impl Default for TimerData {
     fn default() -> Self {
         Self {
@@ -322,7 +1186,13 @@ pub struct NetupiCore {
 
 impl NetupiCore {
     pub async fn new() -> Result<Self, PersistenceError> {
-        let persistence = Arc::new(PersistenceManager::new()?);
+        Self::with_persistence(PersistenceManager::new()?).await
+    }
+
+    pub async fn with_persistence(
+        persistence: PersistenceManager,
+    ) -> Result<Self, PersistenceError> {
+        let persistence = Arc::new(persistence);
         let timer_engine = TimerEngine::new(persistence).await?;
 
         Ok(Self {
@@ -334,6 +1204,15 @@ impl NetupiCore {
         &self.timer_engine
     }
 
+    pub fn persistence(&self) -> &PersistenceManager {
+        &self.timer_engine.persistence
+    }
+
+    /// The configured calendar, for showing times in the user's time zone.
+    pub async fn calendar(&self) -> Result<Calendar, PersistenceError> {
+        self.timer_engine.calendar().await
+    }
+
     pub async fn start_work_session(
         &self,
         project_name: String,
@@ -344,6 +1223,255 @@ impl NetupiCore {
             .await
     }
 
+    /// Start a Pomodoro work session. Without a project, the session continues
+    /// the project of the current Pomodoro cycle (if any). A preset applies
+    /// to this cycle only; without one the cycle keeps its current rhythm.
+    pub async fn start_pomodoro(
+        &self,
+        project_name: Option<String>,
+        description: Option<String>,
+        preset: Option<String>,
+    ) -> Result<(), PersistenceError> {
+        if preset.is_some() {
+            self.timer_engine.set_cycle_preset(preset).await?;
+        }
+        self.timer_engine
+            .start_work_session(TimerType::PomodoroWork, project_name, description)
+            .await
+    }
+
+    /// The running timer and session, with the budget of its project.
+    pub async fn status(&self) -> Result<TimerStatus, PersistenceError> {
+        let session = self.timer_engine.current_session().await;
+        let budget = match session.as_ref().and_then(|s| s.project()) {
+            Some(project) => self.get_budget_status(project).await?,
+            None => None,
+        };
+        Ok(TimerStatus {
+            timer: self.timer_engine.get_current_state().await,
+            session,
+            cycle_preset: self.timer_engine.cycle_preset().await,
+            budget,
+        })
+    }
+
+    /// Like [`NetupiCore::status`], but when this process isn't timing
+    /// anything, the timer another `netupi` process is running, if any.
+    pub async fn active_status(&self) -> Result<TimerStatus, PersistenceError> {
+        let status = self.status().await?;
+        if status.timer.state != TimerState::Idle {
+            return Ok(status);
+        }
+        let Some(active) = self.timer_engine.persistence.load_active_timer().await? else {
+            return Ok(status);
+        };
+        let budget = match active.session.as_ref().and_then(|s| s.project()) {
+            Some(project) => self.get_budget_status(project).await?,
+            None => None,
+        };
+        let mut timer = active.timer;
+        timer.elapsed = timer.elapsed_at(Utc::now());
+        Ok(TimerStatus {
+            timer,
+            session: active.session,
+            cycle_preset: active.cycle_preset,
+            budget,
+        })
+    }
+
+    /// Start a timer, stopping and saving the running one first.
+    pub async fn start(&self, request: StartRequest) -> Result<(), PersistenceError> {
+        self.check_start(&request).await?;
+        if self.timer_engine.get_current_state().await.state != TimerState::Idle {
+            self.timer_engine.stop_timer().await?;
+        }
+        match request {
+            StartRequest::Pomodoro {
+                project,
+                description,
+                preset,
+            } => self.start_pomodoro(project, description, preset).await,
+            StartRequest::ShortBreak => {
+                self.timer_engine
+                    .start_timer(TimerType::PomodoroShortBreak)
+                    .await
+            }
+            StartRequest::LongBreak => {
+                self.timer_engine
+                    .start_timer(TimerType::PomodoroLongBreak)
+                    .await
+            }
+            StartRequest::Stopwatch => self.timer_engine.start_timer(TimerType::Stopwatch).await,
+            StartRequest::Project {
+                project,
+                description,
+                billable,
+                rate,
+            } => {
+                self.start_work_session(project, description).await?;
+                if billable.is_some() || rate.is_some() {
+                    self.timer_engine.set_billing_override(billable, rate).await;
+                }
+                Ok(())
+            }
+            StartRequest::Countdown { duration, label } => {
+                self.start_countdown(duration, label).await
+            }
+        }
+    }
+
+    /// Start a timer as if it had been started at `at`, e.g. from
+    /// [`when::parse_instant`]. A running timer is stopped at `at` too, as
+    /// if switching then; otherwise `at` can't be before the end of the last
+    /// saved session.
+    pub async fn start_at(
+        &self,
+        request: StartRequest,
+        at: DateTime<Utc>,
+    ) -> Result<(), PersistenceError> {
+        self.check_start(&request).await?;
+        if at > Utc::now() {
+            return Err(PersistenceError::Config(
+                "can't start a timer in the future".to_string(),
+            ));
+        }
+        let running = self.timer_engine.get_current_state().await;
+        if running.state != TimerState::Idle {
+            self.timer_engine.stop_timer_at(at).await?;
+        } else {
+            let sessions = self.timer_engine.persistence.load_sessions().await?;
+            if let Some(last_end) = sessions.iter().filter_map(|s| s.end_time).max()
+                && at < last_end
+            {
+                let calendar = self.timer_engine.calendar().await?;
+                return Err(PersistenceError::Config(format!(
+                    "can't start before the last session ended ({})",
+                    calendar.local(last_end).format("%Y-%m-%d %H:%M")
+                )));
+            }
+        }
+        self.start(request).await?;
+        self.timer_engine.backdate(at).await;
+        Ok(())
+    }
+
+    /// Check a start request before stopping a timer for a start that would
+    /// fail.
+    async fn check_start(&self, request: &StartRequest) -> Result<(), PersistenceError> {
+        if let StartRequest::Pomodoro {
+            preset: Some(name), ..
+        } = request
+        {
+            self.timer_engine.get_config().await.preset(name)?;
+        }
+        Ok(())
+    }
+
+    /// Stop and save the running timer. Returns its status just before it
+    /// stopped, or `None` if no timer was running.
+    pub async fn stop(&self) -> Result<Option<TimerStatus>, PersistenceError> {
+        self.stop_at(Utc::now()).await
+    }
+
+    /// Stop and save the running timer as if it had been stopped at `at`,
+    /// e.g. from [`when::parse_instant`]. Returns its status as of `at`, or
+    /// `None` if no timer was running.
+    pub async fn stop_at(
+        &self,
+        at: DateTime<Utc>,
+    ) -> Result<Option<TimerStatus>, PersistenceError> {
+        let mut status = self.status().await?;
+        if status.timer.state == TimerState::Idle {
+            return Ok(None);
+        }
+        self.timer_engine.stop_timer_at(at).await?;
+        status.timer.elapsed = status.timer.elapsed_until(at);
+        Ok(Some(status))
+    }
+
+    /// Start a new session with the project, tags, description and billing
+    /// of the `n`-th most recent work session (1 is the last one), stopping
+    /// the running timer first. When `n` is 1, the timer was idle and the
+    /// session ended at most `resume_last` ago, that session is extended
+    /// instead. Returns whether it was.
+    pub async fn continue_session(&self, n: usize) -> Result<bool, PersistenceError> {
+        let mut sessions: Vec<WorkSession> = self
+            .get_sessions()
+            .await?
+            .into_iter()
+            .filter(|s| s.session_type.is_work() && s.end_time.is_some())
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.end_time));
+        let count = sessions.len();
+        let Some(session) = n.checked_sub(1).and_then(|i| sessions.into_iter().nth(i)) else {
+            return Err(PersistenceError::Config(match count {
+                0 => "no session to continue yet".to_string(),
+                1 => "there is only one session to continue".to_string(),
+                _ => format!("there are only {} sessions to continue", count),
+            }));
+        };
+
+        let idle = self.timer_engine.get_current_state().await.state == TimerState::Idle;
+        let resume_last = self.timer_engine.get_config().await.resume_last;
+        let recent = match (resume_last, session.end_time) {
+            (Some(limit), Some(end)) => Utc::now() - end <= limit,
+            _ => false,
+        };
+        if n == 1
+            && idle
+            && recent
+            && session.target_duration.is_none()
+            && session.invoice.is_none()
+        {
+            self.timer_engine.resume_session(session).await?;
+            return Ok(true);
+        }
+
+        let project = session.project().map(str::to_string);
+        let request = match (session.session_type, project) {
+            (TimerType::PomodoroWork, project) => StartRequest::Pomodoro {
+                project,
+                description: None,
+                preset: None,
+            },
+            (_, Some(project)) => StartRequest::Project {
+                project,
+                description: None,
+                billable: session.billable,
+                rate: session.hourly_rate,
+            },
+            (_, None) => StartRequest::Stopwatch,
+        };
+        self.start(request).await?;
+        // Copied here, as a plain stopwatch request can't carry them.
+        if let Some(current) = self.timer_engine.current_session.write().await.as_mut() {
+            current.tags = session.tags;
+            current.description = session.description;
+        }
+        let data = self.timer_engine.data.read().await.clone();
+        self.timer_engine.save_active(&data).await;
+        Ok(false)
+    }
+
+    /// Make a preset the default rhythm and save the config. The current
+    /// cycle switches to it too.
+    pub async fn use_preset(&self, name: &str) -> Result<(), PersistenceError> {
+        let mut config = self.timer_engine.get_config().await;
+        config.use_preset(name)?;
+        self.timer_engine.set_config(config).await?;
+        self.timer_engine.set_cycle_preset(None).await
+    }
+
+    /// Start a custom countdown of `duration`, e.g. from
+    /// [`duration::parse_duration`].
+    pub async fn start_countdown(
+        &self,
+        duration: Duration,
+        label: Option<String>,
+    ) -> Result<(), PersistenceError> {
+        self.timer_engine.start_countdown(duration, label).await
+    }
+
     pub async fn get_sessions(&self) -> Result<Vec<WorkSession>, PersistenceError> {
         self.timer_engine.persistence.load_sessions().await
     }
@@ -352,14 +1480,14 @@ impl NetupiCore {
         let sessions = self.timer_engine.persistence.load_sessions().await?;
         let mut project_totals: HashMap<String, Duration> = HashMap::new();
 
-        for session in sessions {
-            if session.session_type == TimerType::Stopwatch
+        for session in &sessions {
+            if let Some(project) = session.project()
                 && session.end_time.is_some()
-                && !session.tags.is_empty()
             {
-                let project = session.tags[0].clone();
                 let duration = session.duration;
-                *project_totals.entry(project).or_insert(Duration::zero()) += duration;
+                *project_totals
+                    .entry(project.to_string())
+                    .or_insert(Duration::zero()) += duration;
             }
         }
 
@@ -369,27 +1497,45 @@ impl NetupiCore {
     }
 
     pub async fn get_today_summary(&self) -> Result<HashMap<String, Duration>, PersistenceError> {
-        let sessions = self.timer_engine.persistence.load_sessions().await?;
-        let today = Utc::now().date_naive();
-        let mut project_totals: HashMap<String, Duration> = HashMap::new();
+        let summary = self.get_period_summary(PeriodKind::Day, 0).await?;
+        Ok(summary
+            .projects
+            .into_iter()
+            .map(|project| (project.name, project.total))
+            .collect())
+    }
 
-        for session in sessions {
-            if let Some(end_time) = session.end_time {
-                let session_date = end_time.date_naive();
-                if session_date == today
-                    && session.session_type == TimerType::Stopwatch
-                    && !session.tags.is_empty()
-                {
-                    let project = session.tags[0].clone();
-                    *project_totals.entry(project).or_insert(Duration::zero()) += session.duration;
-                }
-            }
-        }
+    /// Per-project and per-day totals for the current day, week or month, or
+    /// for the one `offset` periods back.
+    pub async fn get_period_summary(
+        &self,
+        kind: PeriodKind,
+        offset: u32,
+    ) -> Result<PeriodSummary, PersistenceError> {
+        let calendar = self.timer_engine.calendar().await?;
+        let period = Period::containing(kind, calendar.today(), &calendar).back(offset);
+        self.get_summary_for(period).await
+    }
+
+    /// Total project time on each day in `start..end`.
+    pub async fn get_daily_totals(
+        &self,
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    ) -> Result<Vec<Duration>, PersistenceError> {
+        let sessions = self.timer_engine.persistence.load_sessions().await?;
+        let calendar = self.timer_engine.calendar().await?;
+        Ok(period::daily_totals(&sessions, start, end, &calendar))
+    }
 
-        Ok(project_totals)
+    /// Per-project and per-day totals for a given period.
+    pub async fn get_summary_for(&self, period: Period) -> Result<PeriodSummary, PersistenceError> {
+        let sessions = self.timer_engine.persistence.load_sessions().await?;
+        let calendar = self.timer_engine.calendar().await?;
+        Ok(period::aggregate(&sessions, period, &calendar))
     }
     
-    /// Get all completed stopwatch sessions for a specific project (first tag match).
+    /// Get all completed work sessions (stopwatch and Pomodoro) for a specific project (first tag match).
     /// Returns sorted by end_time descending (newest first).
     pub async fn get_sessions_for_project(
         &self,
@@ -398,12 +1544,7 @@ impl NetupiCore {
         let sessions = self.timer_engine.persistence.load_sessions().await?;
         let mut project_sessions: Vec<WorkSession> = sessions
             .into_iter()
-            .filter(|s| {
-                s.session_type == TimerType::Stopwatch
-                    && s.end_time.is_some()
-                    && !s.tags.is_empty()
-                    && s.tags[0] == project
-            })
+            .filter(|s| s.end_time.is_some() && s.project() == Some(project))
             .collect();
         // Sort by end_time descending (newest first)
         project_sessions.sort_by(|a, b| {
@@ -414,33 +1555,221 @@ impl NetupiCore {
         Ok(project_sessions)
     }
 
-    /// Delete all completed stopwatch sessions for a specific project (first tag match).
+    /// Delete all completed sessions for a specific project (first tag match),
+    /// including Pomodoro breaks taken while working on it.
     /// Returns the number of sessions deleted.
     pub async fn delete_project_sessions(
         &self,
         project: &str,
     ) -> Result<usize, PersistenceError> {
-        let mut sessions = self.timer_engine.persistence.load_sessions().await?;
-        let before_len = sessions.len();
-        sessions.retain(|s| {
-            !(s.session_type == TimerType::Stopwatch
-                && s.end_time.is_some()
-                && !s.tags.is_empty()
-                && s.tags[0] == project)
-        });
-        let deleted_count = before_len - sessions.len();
-        if deleted_count > 0 {
-            let json = serde_json::to_string_pretty(&sessions)?;
-            tokio::fs::write(
-                &self.timer_engine.persistence.sessions_file,
-                json,
-            )
-            .await?;
+        self.timer_engine
+            .persistence
+            .update_sessions(|sessions| {
+                let before_len = sessions.len();
+                sessions.retain(|s| {
+                    !(s.end_time.is_some() && s.tags.first().is_some_and(|t| t == project))
+                });
+                before_len - sessions.len()
+            })
+            .await
+    }
+
+    pub async fn get_project_settings(
+        &self,
+        project: &str,
+    ) -> Result<ProjectSettings, PersistenceError> {
+        let persistence = &self.timer_engine.persistence;
+        let mut settings = persistence.load_project_settings().await?;
+        Ok(settings.remove(project).unwrap_or_default())
+    }
+
+    pub async fn set_project_settings(
+        &self,
+        project: &str,
+        project_settings: ProjectSettings,
+    ) -> Result<(), PersistenceError> {
+        let persistence = &self.timer_engine.persistence;
+        let mut settings = persistence.load_project_settings().await?;
+        settings.insert(project.to_string(), project_settings);
+        persistence.save_project_settings(&settings).await
+    }
+
+    /// Per-project totals, billable time and earnings across all sessions.
+    pub async fn get_project_summaries(&self) -> Result<Vec<ProjectSummary>, PersistenceError> {
+        self.get_report(None, None).await
+    }
+
+    /// Per-project totals, billable time and earnings for the time worked today.
+    pub async fn get_today_project_summaries(
+        &self,
+    ) -> Result<Vec<ProjectSummary>, PersistenceError> {
+        let calendar = self.timer_engine.calendar().await?;
+        let (day_start, day_end) = calendar.day_range(calendar.today());
+        let persistence = &self.timer_engine.persistence;
+        let sessions = persistence.load_sessions().await?;
+        let settings = persistence.load_project_settings().await?;
+        let rounding = self.timer_engine.get_config().await.rounding;
+        Ok(billing::summarize_between(
+            &sessions,
+            &settings,
+            rounding,
+            Some(day_start),
+            Some(day_end),
+        ))
+    }
+
+    /// Per-project totals, billable time and earnings for the time worked
+    /// within the last `days` calendar days including today (all time if
+    /// `None`), optionally limited to a single project.
+    pub async fn get_report(
+        &self,
+        days: Option<u32>,
+        project: Option<&str>,
+    ) -> Result<Vec<ProjectSummary>, PersistenceError> {
+        let today = self.timer_engine.calendar().await?.today();
+        let from = days.map(|d| today - Duration::days(d.saturating_sub(1) as i64));
+        self.get_report_between(from, None, project).await
+    }
+
+    /// Per-project totals, billable time and earnings for the time worked
+    /// on the calendar days `from..=to`, either end open if `None`,
+    /// optionally limited to a single project.
+    pub async fn get_report_between(
+        &self,
+        from: Option<chrono::NaiveDate>,
+        to: Option<chrono::NaiveDate>,
+        project: Option<&str>,
+    ) -> Result<Vec<ProjectSummary>, PersistenceError> {
+        let persistence = &self.timer_engine.persistence;
+        let sessions = persistence.load_sessions().await?;
+        let settings = persistence.load_project_settings().await?;
+        let calendar = self.timer_engine.calendar().await?;
+        let since = from.map(|day| calendar.day_start(day));
+        let until = to.map(|day| calendar.day_start(day + Duration::days(1)));
+        let sessions: Vec<WorkSession> = sessions
+            .into_iter()
+            .filter(|s| project.is_none_or(|p| s.tags.first().is_some_and(|t| t == p)))
+            .collect();
+        let rounding = self.timer_engine.get_config().await.rounding;
+        Ok(billing::summarize_between(
+            &sessions, &settings, rounding, since, until,
+        ))
+    }
+
+    /// Build an invoice from the project's billable, not yet invoiced
+    /// sessions and mark them as invoiced (unless `dry_run` is set).
+    /// Without an explicit rounding rule, the project's (or the global) rule
+    /// is used. Returns the invoice and the rendered document.
+    pub async fn create_invoice(
+        &self,
+        options: &InvoiceOptions,
+        format: InvoiceFormat,
+    ) -> Result<(Invoice, String), PersistenceError> {
+        let persistence = &self.timer_engine.persistence;
+        let settings = persistence.load_project_settings().await?;
+
+        let project_settings = settings.get(&options.project);
+        let mut options = options.clone();
+        if options.rounding.is_none() {
+            let default = self.timer_engine.get_config().await.rounding;
+            options.rounding = Some(billing::rounding(project_settings, default));
         }
-        Ok(deleted_count)
+
+        let calendar = self.timer_engine.calendar().await?;
+        let build = |sessions: &[WorkSession]| {
+            let number = options
+                .number
+                .clone()
+                .unwrap_or_else(|| invoice::next_invoice_number(sessions));
+            invoice::build_invoice(sessions, project_settings, &options, number, &calendar)
+        };
+        // Numbering and marking happen under the sessions lock, so two
+        // invoices made at once get different numbers and sessions.
+        let invoice = if options.dry_run {
+            build(&persistence.load_sessions().await?)
+        } else {
+            persistence
+                .update_sessions(|sessions| {
+                    let invoice = build(sessions);
+                    for session in sessions
+                        .iter_mut()
+                        .filter(|s| invoice.session_ids.contains(&s.id))
+                    {
+                        session.invoice = Some(invoice.number.clone());
+                    }
+                    invoice
+                })
+                .await?
+        };
+
+        let template = persistence.load_invoice_template(format).await?;
+        let rendered = invoice::render(&invoice, &template, format, &calendar);
+
+        Ok((invoice, rendered))
     }
 
-    
+    /// Progress towards each configured daily/weekly goal, with streaks.
+    pub async fn get_goal_progress(&self) -> Result<Vec<GoalProgress>, PersistenceError> {
+        let sessions = self.timer_engine.persistence.load_sessions().await?;
+        let config = self.timer_engine.get_config().await;
+        let calendar = Calendar::from_config(&config.calendar)?;
+        Ok(goals::progress(
+            &sessions,
+            &config,
+            &calendar,
+            calendar.today(),
+        ))
+    }
+
+    /// Pomodoros completed today since the last long break, out of the
+    /// active preset's `long_break_interval`.
+    pub async fn cycle_position(&self) -> Result<u32, PersistenceError> {
+        let sessions = self.timer_engine.persistence.load_sessions().await?;
+        let config = self.timer_engine.get_config().await;
+        let interval = self.timer_engine.rhythm().await.long_break_interval;
+        let calendar = Calendar::from_config(&config.calendar)?;
+        let today = calendar.day_start(calendar.today());
+        Ok(goals::cycle_position(&sessions, &config, interval, today))
+    }
+
+    /// Pomodoro statistics over the last `days` calendar days, including today.
+    pub async fn get_pomodoro_stats(&self, days: u32) -> Result<PomodoroStats, PersistenceError> {
+        let sessions = self.timer_engine.persistence.load_sessions().await?;
+        let config = self.timer_engine.get_config().await;
+        let calendar = Calendar::from_config(&config.calendar)?;
+        let end = calendar.today() + Duration::days(1);
+        let start = end - Duration::days(days.max(1) as i64);
+        Ok(stats::pomodoro_stats(
+            &sessions, &config, &calendar, start, end,
+        ))
+    }
+
+    /// Budget usage for a project in its current period, including the
+    /// running session if it belongs to the project. `None` without a budget.
+    pub async fn get_budget_status(
+        &self,
+        project: &str,
+    ) -> Result<Option<BudgetStatus>, PersistenceError> {
+        let Some(budget) = self.get_project_settings(project).await?.budget else {
+            return Ok(None);
+        };
+
+        let mut sessions = self.timer_engine.persistence.load_sessions().await?;
+        let current = self.timer_engine.current_session().await;
+        if let Some(current) = &current {
+            sessions.retain(|s| s.id != current.id);
+        }
+        let calendar = self.timer_engine.calendar().await?;
+        let mut consumed =
+            budget::consumed(&sessions, project, budget.period, Utc::now(), &calendar);
+        if let Some(current) = current
+            && current.project() == Some(project)
+        {
+            consumed += self.timer_engine.get_current_state().await.elapsed;
+        }
+
+        Ok(Some(BudgetStatus { budget, consumed }))
+    }
 }
 
 // =============================================================================
@@ -450,16 +1779,19 @@ impl NetupiCore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{SessionBuilder, core_in, test_core};
 
     #[tokio::test]
     async fn test_core_creation() {
-        let core = NetupiCore::new().await;
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = PersistenceManager::with_data_dir(dir.path().to_path_buf()).unwrap();
+        let core = NetupiCore::with_persistence(persistence).await;
         assert!(core.is_ok(), "Core creation should succeed");
     }
 
     #[tokio::test]
     async fn test_timer_start() {
-        let core = NetupiCore::new().await.unwrap();
+        let (_dir, core) = test_core().await;
         let result = core.timer_engine.start_timer(TimerType::PomodoroWork).await;
         assert!(result.is_ok(), "Timer start should succeed");
 
@@ -470,7 +1802,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_persistence_manager() {
-        let persistence = PersistenceManager::new();
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = PersistenceManager::with_data_dir(dir.path().to_path_buf());
         assert!(
             persistence.is_ok(),
             "PersistenceManager creation should succeed"
@@ -494,18 +1827,15 @@ mod tests {
 
     #[tokio::test]
     async fn test_session_persistence() {
-        let persistence = PersistenceManager::new().unwrap();
+        let (_dir, core) = test_core().await;
+        let persistence = core.persistence();
 
-        let session = WorkSession {
-            id: uuid::Uuid::new_v4(),
-            session_type: TimerType::PomodoroWork,
-            start_time: Utc::now(),
-            end_time: None,
-            duration: Duration::minutes(25),
-            paused_duration: Duration::zero(),
-            description: Some("Test session".to_string()),
-            tags: vec!["test".to_string()],
-        };
+        let session = SessionBuilder::starting(Utc::now(), Duration::minutes(25))
+            .kind(TimerType::PomodoroWork)
+            .project("test")
+            .description("Test session")
+            .running()
+            .build();
 
         let save_result = persistence.save_session(&session).await;
         assert!(save_result.is_ok(), "Session save should succeed");
@@ -517,4 +1847,556 @@ mod tests {
         assert!(!sessions.is_empty(), "Should have at least one session");
         assert_eq!(sessions.last().unwrap().id, session.id);
     }
+
+    #[tokio::test]
+    async fn test_budget_threshold_events() {
+        let (_dir, core) = test_core().await;
+
+        let settings = ProjectSettings {
+            budget: Some(Budget {
+                limit: Duration::seconds(1),
+                period: budget::BudgetPeriod::Total,
+            }),
+            ..Default::default()
+        };
+        core.set_project_settings("api", settings).await.unwrap();
+
+        let mut events = core.timer().subscribe_events();
+        core.start_work_session("api".to_string(), None)
+            .await
+            .unwrap();
+
+        let mut crossed = Vec::new();
+        let wait = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while let Some(event) = events.recv().await {
+                if let TimerEvent::BudgetThreshold { project, percent } = event {
+                    assert_eq!(project, "api");
+                    crossed.push(percent);
+                    if percent == 100 {
+                        break;
+                    }
+                }
+            }
+        });
+        assert!(wait.await.is_ok(), "Budget alerts should arrive");
+        assert_eq!(crossed, vec![80, 100]);
+
+        let status = core.get_budget_status("api").await.unwrap().unwrap();
+        assert!(status.percent() >= 100.0);
+        core.timer().stop_timer().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_budget_skips_running_breaks() {
+        let (_dir, core) = test_core().await;
+        let settings = ProjectSettings {
+            budget: Some(Budget {
+                limit: Duration::hours(10),
+                period: budget::BudgetPeriod::Total,
+            }),
+            ..Default::default()
+        };
+        core.set_project_settings("api", settings).await.unwrap();
+
+        core.start_pomodoro(Some("api".to_string()), None, None)
+            .await
+            .unwrap();
+        core.timer().stop_timer().await.unwrap();
+        // The break carries the cycle's project, but isn't time spent on it.
+        core.timer()
+            .start_timer(TimerType::PomodoroShortBreak)
+            .await
+            .unwrap();
+        assert!(core.timer().backdate(Utc::now() - Duration::hours(1)).await);
+        let status = core.get_budget_status("api").await.unwrap().unwrap();
+        assert!(status.consumed < Duration::minutes(1));
+        core.timer().stop_timer().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pomodoro_cycle_keeps_project() {
+        let (_dir, core) = test_core().await;
+
+        core.start_pomodoro(Some("api".to_string()), None, None)
+            .await
+            .unwrap();
+        core.timer().stop_timer().await.unwrap();
+        core.timer()
+            .start_timer(TimerType::PomodoroShortBreak)
+            .await
+            .unwrap();
+        core.timer().stop_timer().await.unwrap();
+        core.start_pomodoro(None, None, None).await.unwrap();
+        core.timer().stop_timer().await.unwrap();
+
+        let sessions = core.get_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions.iter().all(|s| s.tags == ["api"]));
+        assert_eq!(sessions[1].project(), None);
+
+        // Pomodoro work counts towards the project, breaks don't.
+        assert_eq!(core.get_sessions_for_project("api").await.unwrap().len(), 2);
+
+        core.start_work_session("web".to_string(), None)
+            .await
+            .unwrap();
+        core.timer().stop_timer().await.unwrap();
+        assert_eq!(core.timer().cycle_project().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_pomodoro_preset_applies_to_cycle() {
+        let (_dir, core) = test_core().await;
+
+        assert!(
+            core.start_pomodoro(None, None, Some("nap".to_string()))
+                .await
+                .is_err()
+        );
+        core.start_pomodoro(None, None, Some("deep".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            core.timer().get_current_state().await.target_duration,
+            Some(Duration::minutes(52))
+        );
+        core.timer().stop_timer().await.unwrap();
+        core.timer()
+            .start_timer(TimerType::PomodoroShortBreak)
+            .await
+            .unwrap();
+        assert_eq!(
+            core.timer().get_current_state().await.target_duration,
+            Some(Duration::minutes(17))
+        );
+        core.timer().stop_timer().await.unwrap();
+        let sessions = core.get_sessions().await.unwrap();
+        assert_eq!(sessions[0].target_duration, Some(Duration::minutes(52)));
+
+        // A stopwatch ends the cycle, so the default lengths apply again.
+        core.start_work_session("web".to_string(), None)
+            .await
+            .unwrap();
+        core.start_pomodoro(None, None, None).await.unwrap();
+        assert_eq!(
+            core.timer().get_current_state().await.target_duration,
+            Some(Duration::minutes(25))
+        );
+
+        core.use_preset("deep").await.unwrap();
+        assert_eq!(
+            core.timer().get_config().await.active_preset(),
+            Some("deep")
+        );
+        core.timer().stop_timer().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_countdown_completes_and_saves_session() {
+        let (_dir, core) = test_core().await;
+
+        let mut events = core.timer().subscribe_events();
+        core.start_countdown(Duration::milliseconds(500), Some("tea".to_string()))
+            .await
+            .unwrap();
+        let state = core.timer().get_current_state().await;
+        assert_eq!(state.timer_type, TimerType::CustomTimer);
+        assert!(state.remaining().unwrap() <= Duration::milliseconds(500));
+
+        let wait = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while let Some(event) = events.recv().await {
+                if let TimerEvent::Completed { timer_type, label } = event {
+                    return (timer_type, label);
+                }
+            }
+            unreachable!("event channel closed");
+        });
+        let (timer_type, label) = wait.await.expect("Countdown should complete");
+        assert_eq!(timer_type, TimerType::CustomTimer);
+        assert_eq!(label.as_deref(), Some("tea"));
+
+        assert_eq!(
+            core.timer().get_current_state().await.state,
+            TimerState::Idle
+        );
+        let sessions = core.get_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_type, TimerType::CustomTimer);
+        assert!(sessions[0].duration >= Duration::milliseconds(500));
+
+        assert!(core.start_countdown(Duration::zero(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auto_start_breaks() {
+        let (_dir, core) = test_core().await;
+        let mut config = core.timer().get_config().await;
+        config.pomodoro_work_duration = Duration::milliseconds(300);
+        config.pomodoro_short_break_duration = Duration::milliseconds(300);
+        config.auto_start_breaks = true;
+        core.timer().set_config(config).await.unwrap();
+
+        let mut events = core.timer().subscribe_events();
+        core.timer()
+            .start_work_session(TimerType::PomodoroWork, Some("api".to_string()), None)
+            .await
+            .unwrap();
+        async fn completed(events: &mut mpsc::UnboundedReceiver<TimerEvent>) -> TimerType {
+            let wait = async {
+                loop {
+                    match events.recv().await {
+                        Some(TimerEvent::Completed { timer_type, .. }) => return timer_type,
+                        Some(_) => {}
+                        None => unreachable!("event channel closed"),
+                    }
+                }
+            };
+            tokio::time::timeout(std::time::Duration::from_secs(5), wait)
+                .await
+                .expect("the phase should complete")
+        }
+
+        // The Pomodoro is saved and a break starts on its own.
+        assert_eq!(completed(&mut events).await, TimerType::PomodoroWork);
+        assert!(matches!(events.recv().await, Some(TimerEvent::Started)));
+        let state = core.timer().get_current_state().await;
+        assert_eq!(state.timer_type, TimerType::PomodoroShortBreak);
+        assert_eq!(state.state, TimerState::Running);
+        let current = core.timer().current_session().await.unwrap();
+        assert_eq!(current.tags, ["api"]);
+        let sessions = core.get_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_type, TimerType::PomodoroWork);
+
+        // Without auto_start_work, the break keeps running once it's over.
+        assert_eq!(completed(&mut events).await, TimerType::PomodoroShortBreak);
+        let state = core.timer().get_current_state().await;
+        assert_eq!(state.timer_type, TimerType::PomodoroShortBreak);
+        assert_eq!(state.state, TimerState::Running);
+        core.timer().stop_timer().await.unwrap();
+
+        // A preset's own flags and cycle length win over the defaults.
+        let mut config = core.timer().get_config().await;
+        config.auto_start_breaks = false;
+        config.presets.insert(
+            "sprint".to_string(),
+            TimerPreset {
+                work: Duration::milliseconds(300),
+                short_break: Duration::milliseconds(300),
+                long_break: Duration::milliseconds(300),
+                long_break_interval: 2,
+                auto_start_breaks: true,
+                auto_start_work: false,
+            },
+        );
+        core.timer().set_config(config).await.unwrap();
+        core.timer()
+            .set_cycle_preset(Some("sprint".to_string()))
+            .await
+            .unwrap();
+        core.timer()
+            .start_work_session(TimerType::PomodoroWork, None, None)
+            .await
+            .unwrap();
+        assert_eq!(completed(&mut events).await, TimerType::PomodoroWork);
+        assert!(matches!(events.recv().await, Some(TimerEvent::Started)));
+        let state = core.timer().get_current_state().await;
+        assert_eq!(state.timer_type, TimerType::PomodoroLongBreak);
+        assert_eq!(core.cycle_position().await.unwrap(), 2);
+        core.timer().stop_timer().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_start_and_stop_at_earlier_times() {
+        let (_dir, core) = test_core().await;
+        let project = |name: &str| StartRequest::Project {
+            project: name.to_string(),
+            description: None,
+            billable: None,
+            rate: None,
+        };
+        let now = Utc::now();
+
+        core.start_at(project("api"), now - Duration::minutes(90))
+            .await
+            .unwrap();
+        // Switching 30 minutes ago ends the first session then too.
+        core.start_at(project("web"), now - Duration::minutes(30))
+            .await
+            .unwrap();
+        let status = core.stop_at(now - Duration::minutes(10)).await.unwrap();
+        assert_eq!(
+            status.unwrap().timer.elapsed,
+            Duration::minutes(20),
+            "Stopping reports the time worked until then"
+        );
+
+        let sessions = core.get_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].project(), Some("api"));
+        assert_eq!(sessions[0].start_time, now - Duration::minutes(90));
+        assert_eq!(sessions[0].duration, Duration::minutes(60));
+        assert_eq!(sessions[1].project(), Some("web"));
+        assert_eq!(sessions[1].end_time, Some(now - Duration::minutes(10)));
+        assert_eq!(sessions[1].duration, Duration::minutes(20));
+
+        assert!(
+            core.start_at(project("api"), now - Duration::minutes(15))
+                .await
+                .is_err(),
+            "Can't start before the last session ended"
+        );
+        assert!(
+            core.start_at(project("api"), now + Duration::minutes(5))
+                .await
+                .is_err()
+        );
+        core.start(project("api")).await.unwrap();
+        assert!(core.stop_at(now - Duration::minutes(5)).await.is_err());
+        let later = Utc::now() + Duration::minutes(5);
+        assert!(core.stop_at(later).await.is_err());
+        assert!(core.stop().await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_continue_session() {
+        let (_dir, core) = test_core().await;
+        let now = Utc::now();
+        let session = |session_type, tags: &[&str], ended_ago: i64, minutes: i64| {
+            let end = now - Duration::minutes(ended_ago);
+            SessionBuilder::ending(end, Duration::minutes(minutes))
+                .kind(session_type)
+                .tags(tags)
+                .description("auth")
+                .build()
+        };
+        let api = session(TimerType::Stopwatch, &["api", "backend"], 120, 30);
+        let web = session(TimerType::Stopwatch, &["web"], 10, 30);
+        let coffee = session(TimerType::PomodoroShortBreak, &[], 5, 5);
+        for session in [&api, &web, &coffee] {
+            core.persistence().save_session(session).await.unwrap();
+        }
+        let mut config = core.timer().get_config().await;
+        config.resume_last = Some(Duration::minutes(15));
+        core.timer().set_config(config).await.unwrap();
+
+        // The last work session ended 10 minutes ago, so it's extended.
+        assert!(core.continue_session(1).await.unwrap());
+        assert_eq!(core.get_sessions().await.unwrap().len(), 3);
+        let status = core.status().await.unwrap();
+        assert_eq!(status.session.unwrap().id, web.id);
+        assert_eq!(
+            status.timer.elapsed.num_minutes(),
+            30,
+            "The gap counts as a pause"
+        );
+        core.stop().await.unwrap();
+        let sessions = core.get_sessions().await.unwrap();
+        let extended = sessions.iter().find(|s| s.id == web.id).unwrap();
+        assert_eq!(extended.start_time, web.start_time);
+        assert_eq!(extended.duration.num_minutes(), 30);
+        assert_eq!(extended.pause_count, 1);
+
+        // Older sessions are continued in a new one.
+        assert!(!core.continue_session(2).await.unwrap());
+        let current = core.timer().current_session().await.unwrap();
+        assert_ne!(current.id, api.id);
+        assert_eq!(current.tags, ["api", "backend"]);
+        assert_eq!(current.description.as_deref(), Some("auth"));
+        core.stop().await.unwrap();
+        assert_eq!(core.get_sessions().await.unwrap().len(), 4);
+
+        assert!(core.continue_session(0).await.is_err());
+        assert!(core.continue_session(9).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_continue_keeps_session() {
+        let (dir, core) = test_core().await;
+        let end = Utc::now() - Duration::minutes(5);
+        let web = SessionBuilder::ending(end, Duration::minutes(30))
+            .project("web")
+            .build();
+        core.persistence().save_session(&web).await.unwrap();
+        let mut config = core.timer().get_config().await;
+        config.resume_last = Some(Duration::minutes(15));
+        core.timer().set_config(config).await.unwrap();
+
+        assert!(core.continue_session(1).await.unwrap());
+        // The process is killed before the timer stops.
+        drop(core);
+
+        let core = core_in(dir.path()).await;
+        let sessions = core.get_sessions().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, web.id);
+        assert_eq!(sessions[0].end_time, Some(end));
+        assert_eq!(sessions[0].duration, Duration::minutes(30));
+    }
+
+    #[tokio::test]
+    async fn test_config_changes_reach_other_processes() {
+        let (dir, core) = test_core().await;
+        let daemon = core_in(dir.path()).await;
+
+        let mut config = core.timer().get_config().await;
+        config.pomodoro_work_duration = Duration::minutes(50);
+        core.timer().set_config(config).await.unwrap();
+        daemon.start_pomodoro(None, None, None).await.unwrap();
+        assert_eq!(
+            daemon.timer().get_current_state().await.target_duration,
+            Some(Duration::minutes(50))
+        );
+        daemon.timer().stop_timer().await.unwrap();
+
+        // A broken edit by hand keeps the last good configuration.
+        std::fs::write(
+            core.persistence().config_file(),
+            "[timer]\nwork = \"soon\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            daemon.timer().get_config().await.pomodoro_work_duration,
+            Duration::minutes(50)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_saves_keep_every_session() {
+        let (dir, cli) = test_core().await;
+        let daemon = core_in(dir.path()).await;
+
+        let mut saves = tokio::task::JoinSet::new();
+        for i in 0..20 {
+            let core = if i % 2 == 0 {
+                cli.clone()
+            } else {
+                daemon.clone()
+            };
+            saves.spawn(async move {
+                let session = SessionBuilder::ending(Utc::now(), Duration::minutes(1)).build();
+                core.persistence().save_session(&session).await.unwrap();
+            });
+        }
+        saves.join_all().await;
+        assert_eq!(cli.persistence().load_sessions().await.unwrap().len(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_invoices_get_their_own_numbers() {
+        let (dir, core) = test_core().await;
+        let projects = ["a", "b", "c", "d", "e", "f"];
+        for project in projects {
+            let session = SessionBuilder::ending(Utc::now(), Duration::hours(1))
+                .project(project)
+                .billable(true)
+                .build();
+            core.persistence().save_session(&session).await.unwrap();
+        }
+
+        let mut invoices = tokio::task::JoinSet::new();
+        for project in projects {
+            let core = core_in(dir.path()).await;
+            invoices.spawn(async move {
+                let options = InvoiceOptions {
+                    project: project.to_string(),
+                    from: None,
+                    to: None,
+                    grouping: Default::default(),
+                    rounding: None,
+                    number: None,
+                    dry_run: false,
+                };
+                let (invoice, _) = core
+                    .create_invoice(&options, InvoiceFormat::Text)
+                    .await
+                    .unwrap();
+                invoice.number
+            });
+        }
+        let mut numbers = invoices.join_all().await;
+        numbers.sort();
+        let expected: Vec<String> = (1..=6).map(|n| format!("INV-{:04}", n)).collect();
+        assert_eq!(numbers, expected);
+    }
+
+    #[tokio::test]
+    async fn test_active_timer_is_visible_to_other_processes() {
+        let (dir, core) = test_core().await;
+        let other = core_in(dir.path()).await;
+        let persistence = core.persistence();
+        assert!(persistence.load_active_timer().await.unwrap().is_none());
+
+        core.start_pomodoro(Some("api".to_string()), None, None)
+            .await
+            .unwrap();
+        let active = persistence.load_active_timer().await.unwrap().unwrap();
+        assert_eq!(active.pid, std::process::id());
+        assert_eq!(active.timer.state, TimerState::Running);
+        assert_eq!(active.session.unwrap().tags, ["api"]);
+
+        core.timer().pause_timer().await;
+        let status = other.active_status().await.unwrap();
+        assert_eq!(status.timer.state, TimerState::Paused);
+        assert_eq!(status.timer.target_duration, Some(Duration::minutes(25)));
+
+        core.timer().stop_timer().await.unwrap();
+        assert!(persistence.load_active_timer().await.unwrap().is_none());
+        assert_eq!(
+            other.active_status().await.unwrap().timer.state,
+            TimerState::Idle
+        );
+
+        // A file left behind by a process that no longer exists.
+        #[cfg(target_os = "linux")]
+        {
+            let mut active = ActiveTimer {
+                pid: u32::MAX,
+                timer: TimerData::default(),
+                session: None,
+                cycle_preset: None,
+            };
+            persistence.save_active_timer(&active).await.unwrap();
+            assert!(persistence.load_active_timer().await.unwrap().is_none());
+            persistence.clear_active_timer().await.unwrap();
+            assert!(!persistence.active_timer_file().exists());
+
+            // The timer of another live process is left alone.
+            active.pid = 1;
+            persistence.save_active_timer(&active).await.unwrap();
+            persistence.clear_active_timer().await.unwrap();
+            assert_eq!(
+                persistence.load_active_timer().await.unwrap().unwrap().pid,
+                1
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_legacy_json_config_is_migrated_to_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = TimerConfig {
+            pomodoro_work_duration: Duration::minutes(50),
+            ..Default::default()
+        };
+        std::fs::write(
+            dir.path().join("config.json"),
+            serde_json::to_string(&legacy).unwrap(),
+        )
+        .unwrap();
+
+        let persistence = PersistenceManager::with_data_dir(dir.path().to_path_buf()).unwrap();
+        let config = persistence.load_config().await.unwrap();
+        assert_eq!(config.pomodoro_work_duration, Duration::minutes(50));
+
+        let toml = std::fs::read_to_string(dir.path().join("config.toml")).unwrap();
+        assert!(toml.contains("work = \"50m\""));
+        std::fs::write(
+            dir.path().join("config.toml"),
+            toml.replace("work = \"50m\"", "work = \"45m\""),
+        )
+        .unwrap();
+        let config = persistence.load_config().await.unwrap();
+        assert_eq!(config.pomodoro_work_duration, Duration::minutes(45));
+    }
 }
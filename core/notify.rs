@@ -0,0 +1,294 @@
+use crate::{NetupiCore, TimerEvent, TimerType};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use tokio::task::{JoinHandle, JoinSet};
+
+/// Which timer events notify, and through which channels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// A Pomodoro work session reached its length.
+    pub pomodoro: bool,
+    /// A short or long break is over.
+    pub breaks: bool,
+    /// A custom countdown reached zero.
+    pub countdown: bool,
+    /// A project passed one of its budget alert levels.
+    pub budget: bool,
+    /// Ring the terminal bell.
+    pub bell: bool,
+    /// Show a desktop notification (needs the `desktop-notifications` feature).
+    pub desktop: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            pomodoro: true,
+            breaks: true,
+            countdown: true,
+            budget: true,
+            bell: true,
+            desktop: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Pomodoro,
+    Break,
+    Countdown,
+    Budget,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    /// The notification for an event, if it's a kind `config` enables.
+    pub fn from_event(event: &TimerEvent, config: &NotificationConfig) -> Option<Self> {
+        let (kind, title, body) = match event {
+            TimerEvent::Completed { timer_type, label } => match timer_type {
+                TimerType::PomodoroWork => (
+                    NotificationKind::Pomodoro,
+                    "Pomodoro complete".to_string(),
+                    "Time for a break.".to_string(),
+                ),
+                TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak => (
+                    NotificationKind::Break,
+                    "Break is over".to_string(),
+                    "Ready for the next Pomodoro?".to_string(),
+                ),
+                TimerType::Stopwatch | TimerType::CustomTimer => (
+                    NotificationKind::Countdown,
+                    "Time's up".to_string(),
+                    label.clone().unwrap_or_default(),
+                ),
+            },
+            TimerEvent::BudgetThreshold { project, percent } => (
+                NotificationKind::Budget,
+                if *percent >= 100 {
+                    "Budget exhausted".to_string()
+                } else {
+                    "Budget alert".to_string()
+                },
+                format!("'{}' has used {}% of its budget", project, percent),
+            ),
+            _ => return None,
+        };
+        let enabled = match kind {
+            NotificationKind::Pomodoro => config.pomodoro,
+            NotificationKind::Break => config.breaks,
+            NotificationKind::Countdown => config.countdown,
+            NotificationKind::Budget => config.budget,
+        };
+        enabled.then_some(Self { kind, title, body })
+    }
+}
+
+/// Somewhere notifications are delivered. `notify` may block; it runs on
+/// a blocking thread.
+pub trait NotificationSink: Send + Sync {
+    /// Whether this channel is switched on in the config.
+    fn enabled(&self, _config: &NotificationConfig) -> bool {
+        true
+    }
+
+    fn notify(&self, notification: &Notification);
+}
+
+/// Rings the terminal bell.
+pub struct Bell;
+
+impl NotificationSink for Bell {
+    fn enabled(&self, config: &NotificationConfig) -> bool {
+        config.bell
+    }
+
+    fn notify(&self, _notification: &Notification) {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(b"\x07");
+        let _ = stdout.flush();
+    }
+}
+
+/// Shows a desktop notification through the notification server
+/// (freedesktop on Linux).
+#[cfg(feature = "desktop-notifications")]
+pub struct Desktop;
+
+#[cfg(feature = "desktop-notifications")]
+impl NotificationSink for Desktop {
+    fn enabled(&self, config: &NotificationConfig) -> bool {
+        config.desktop
+    }
+
+    fn notify(&self, notification: &Notification) {
+        // No notification server (e.g. over SSH) just means no popup.
+        let _ = notify_rust::Notification::new()
+            .appname("Netupi23")
+            .summary(&notification.title)
+            .body(&notification.body)
+            .show();
+    }
+}
+
+/// The bell, plus desktop notifications when built with them.
+pub fn default_sinks() -> Vec<Arc<dyn NotificationSink>> {
+    vec![
+        Arc::new(Bell),
+        #[cfg(feature = "desktop-notifications")]
+        Arc::new(Desktop),
+    ]
+}
+
+/// Delivers a core's timer completions and budget alerts to a set of sinks,
+/// in the background, until [`Notifier::finish`].
+pub struct Notifier {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl Notifier {
+    pub fn spawn(core: &NetupiCore, sinks: Vec<Arc<dyn NotificationSink>>) -> Self {
+        let core = core.clone();
+        let mut events = core.timer().subscribe_events();
+        let (stop, mut stopped) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            let mut deliveries = JoinSet::new();
+            loop {
+                tokio::select! {
+                    Some(event) = events.recv() => {
+                        deliver(&core, &sinks, &event, &mut deliveries).await;
+                        while deliveries.try_join_next().is_some() {}
+                    }
+                    _ = &mut stopped => break,
+                }
+            }
+            while let Ok(event) = events.try_recv() {
+                deliver(&core, &sinks, &event, &mut deliveries).await;
+            }
+            deliveries.join_all().await;
+        });
+
+        Self { stop, task }
+    }
+
+    /// Deliver the notifications of events already emitted, then stop.
+    pub async fn finish(self) {
+        let _ = self.stop.send(());
+        let _ = self.task.await;
+    }
+}
+
+async fn deliver(
+    core: &NetupiCore,
+    sinks: &[Arc<dyn NotificationSink>],
+    event: &TimerEvent,
+    deliveries: &mut JoinSet<()>,
+) {
+    let config = core.timer().get_config().await.notifications;
+    let Some(notification) = Notification::from_event(event, &config) else {
+        return;
+    };
+    for sink in sinks.iter().filter(|sink| sink.enabled(&config)) {
+        let (sink, notification) = (sink.clone(), notification.clone());
+        deliveries.spawn_blocking(move || sink.notify(&notification));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_core;
+    use chrono::Duration;
+    use std::sync::Mutex;
+
+    /// Records what it's sent.
+    #[derive(Default)]
+    struct MockSink(Mutex<Vec<Notification>>);
+
+    impl NotificationSink for MockSink {
+        fn notify(&self, notification: &Notification) {
+            self.0.lock().unwrap().push(notification.clone());
+        }
+    }
+
+    /// Records what it's sent while the bell is switched on.
+    #[derive(Default)]
+    struct MockBell(Mutex<Vec<Notification>>);
+
+    impl NotificationSink for MockBell {
+        fn enabled(&self, config: &NotificationConfig) -> bool {
+            config.bell
+        }
+
+        fn notify(&self, notification: &Notification) {
+            self.0.lock().unwrap().push(notification.clone());
+        }
+    }
+
+    #[test]
+    fn test_notification_from_event() {
+        let mut config = NotificationConfig::default();
+        let pomodoro = TimerEvent::Completed {
+            timer_type: TimerType::PomodoroWork,
+            label: None,
+        };
+        let budget = TimerEvent::BudgetThreshold {
+            project: "api".to_string(),
+            percent: 100,
+        };
+        assert_eq!(
+            Notification::from_event(&pomodoro, &config).unwrap().kind,
+            NotificationKind::Pomodoro
+        );
+        let alert = Notification::from_event(&budget, &config).unwrap();
+        assert_eq!(alert.title, "Budget exhausted");
+        assert_eq!(alert.body, "'api' has used 100% of its budget");
+        assert!(Notification::from_event(&TimerEvent::Started, &config).is_none());
+
+        config.pomodoro = false;
+        assert!(Notification::from_event(&pomodoro, &config).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_notifier_delivers_enabled_events() {
+        let (_dir, core) = test_core().await;
+        let (sink, bell) = (Arc::new(MockSink::default()), Arc::new(MockBell::default()));
+        let mut events = core.timer().subscribe_events();
+        // Only the first countdown notifies, and never through the bell. The
+        // notifier reads the config as it delivers, so it is finished before
+        // the config changes.
+        for countdown in [true, false] {
+            let mut config = core.timer().get_config().await;
+            config.notifications.bell = false;
+            config.notifications.countdown = countdown;
+            core.timer().set_config(config).await.unwrap();
+            let notifier = Notifier::spawn(&core, vec![sink.clone(), bell.clone()]);
+            core.start_countdown(Duration::milliseconds(50), Some("tea".to_string()))
+                .await
+                .unwrap();
+            while !matches!(events.recv().await, Some(TimerEvent::Completed { .. })) {}
+            notifier.finish().await;
+        }
+
+        assert_eq!(
+            *sink.0.lock().unwrap(),
+            [Notification {
+                kind: NotificationKind::Countdown,
+                title: "Time's up".to_string(),
+                body: "tea".to_string(),
+            }]
+        );
+        assert!(bell.0.lock().unwrap().is_empty());
+    }
+}
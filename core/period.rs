@@ -0,0 +1,246 @@
+use crate::WorkSession;
+use crate::calendar::Calendar;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeriodKind {
+    Day,
+    Week,
+    Month,
+}
+
+/// A range of calendar days, `start..end` (end exclusive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Period {
+    pub kind: PeriodKind,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Period {
+    /// The period of the given kind that contains `day`.
+    pub fn containing(kind: PeriodKind, day: NaiveDate, calendar: &Calendar) -> Self {
+        let start = match kind {
+            PeriodKind::Day => day,
+            PeriodKind::Week => calendar.week_of(day),
+            PeriodKind::Month => day.with_day(1).unwrap_or(day),
+        };
+        Self::starting_at(kind, start)
+    }
+
+    fn starting_at(kind: PeriodKind, start: NaiveDate) -> Self {
+        let end = match kind {
+            PeriodKind::Day => start + Duration::days(1),
+            PeriodKind::Week => start + Duration::weeks(1),
+            PeriodKind::Month => start + Months::new(1),
+        };
+        Self { kind, start, end }
+    }
+
+    /// The period `offset` periods before this one (0 is this period).
+    pub fn back(&self, offset: u32) -> Self {
+        let start = match self.kind {
+            PeriodKind::Day => self.start - Duration::days(offset as i64),
+            PeriodKind::Week => self.start - Duration::weeks(offset as i64),
+            PeriodKind::Month => self.start - Months::new(offset),
+        };
+        Self::starting_at(self.kind, start)
+    }
+
+    pub fn previous(&self) -> Self {
+        self.back(1)
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.start.iter_days().take_while(|day| *day < self.end)
+    }
+
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        self.start <= day && day < self.end
+    }
+}
+
+/// Time tracked on one project within a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectPeriodTotals {
+    pub name: String,
+    pub total: Duration,
+    /// One entry per day of the period, in order.
+    pub per_day: Vec<Duration>,
+}
+
+/// Per-project and per-day totals for a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodSummary {
+    pub period: Period,
+    /// Sorted by project name.
+    pub projects: Vec<ProjectPeriodTotals>,
+    pub total: Duration,
+}
+
+impl PeriodSummary {
+    /// Total time on `project`, zero if it wasn't worked on in the period.
+    pub fn project_total(&self, project: &str) -> Duration {
+        self.projects
+            .iter()
+            .find(|p| p.name == project)
+            .map_or(Duration::zero(), |p| p.total)
+    }
+
+    /// Total time across all projects on each day of the period.
+    pub fn daily_totals(&self) -> Vec<Duration> {
+        let mut totals = vec![Duration::zero(); self.period.days().count()];
+        for project in &self.projects {
+            for (total, day) in totals.iter_mut().zip(&project.per_day) {
+                *total += *day;
+            }
+        }
+        totals
+    }
+}
+
+/// Project time (completed work sessions, keyed by their first tag)
+/// worked on each day in `start..end`, one entry per day. Sessions spanning
+/// several days only contribute the part worked on each day.
+fn project_days(
+    sessions: &[WorkSession],
+    start: NaiveDate,
+    end: NaiveDate,
+    calendar: &Calendar,
+) -> BTreeMap<String, Vec<Duration>> {
+    let day_count = (end - start).num_days().max(0) as usize;
+    let mut projects: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+
+    for session in sessions {
+        let Some(project) = session.project() else {
+            continue;
+        };
+
+        for (day, part) in calendar.split_by_day(session) {
+            if day < start || day >= end {
+                continue;
+            }
+            let index = (day - start).num_days() as usize;
+            projects
+                .entry(project.to_string())
+                .or_insert_with(|| vec![Duration::zero(); day_count])[index] += part.duration;
+        }
+    }
+    projects
+}
+
+/// Per-project and per-day totals for `period`.
+pub fn aggregate(sessions: &[WorkSession], period: Period, calendar: &Calendar) -> PeriodSummary {
+    let projects: Vec<ProjectPeriodTotals> =
+        project_days(sessions, period.start, period.end, calendar)
+            .into_iter()
+            .map(|(name, per_day)| ProjectPeriodTotals {
+                name,
+                total: per_day.iter().copied().sum(),
+                per_day,
+            })
+            .collect();
+    let total = projects.iter().map(|p| p.total).sum();
+
+    PeriodSummary {
+        period,
+        projects,
+        total,
+    }
+}
+
+/// Total project time on each day in `start..end`, for ranges that don't
+/// match a day, week or month (such as the last year).
+pub fn daily_totals(
+    sessions: &[WorkSession],
+    start: NaiveDate,
+    end: NaiveDate,
+    calendar: &Calendar,
+) -> Vec<Duration> {
+    let mut totals = vec![Duration::zero(); (end - start).num_days().max(0) as usize];
+    for per_day in project_days(sessions, start, end, calendar).values() {
+        for (total, day) in totals.iter_mut().zip(per_day) {
+            *total += *day;
+        }
+    }
+    totals
+}
+
+impl FromStr for PeriodKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" | "today" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            other => Err(format!(
+                "unknown period '{}' (expected day, week or month)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PeriodKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SessionBuilder;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_week_aggregation_and_offsets() {
+        let calendar = Calendar::utc();
+        let session = |day: u32, hour: u32, hours: i64, project: &str| {
+            let start = Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+            SessionBuilder::starting(start, Duration::hours(hours))
+                .project(project)
+                .build()
+        };
+        let sessions = vec![
+            // Sunday night into Monday: only the Monday hour is this week.
+            session(1, 23, 2, "api"),
+            session(3, 9, 3, "api"),
+            session(4, 9, 2, "web"),
+        ];
+
+        let wednesday = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let week = Period::containing(PeriodKind::Week, wednesday, &calendar);
+        assert_eq!(week.start, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+
+        let summary = aggregate(&sessions, week, &calendar);
+        assert_eq!(summary.total, Duration::hours(6));
+        assert_eq!(summary.project_total("api"), Duration::hours(4));
+        assert_eq!(summary.projects[0].per_day[0], Duration::hours(1));
+        assert_eq!(summary.daily_totals()[2], Duration::hours(2));
+
+        let previous = aggregate(&sessions, week.previous(), &calendar);
+        assert_eq!(previous.total, Duration::hours(1));
+
+        let days = daily_totals(&sessions, previous.period.start, week.end, &calendar);
+        assert_eq!(days.len(), 14);
+        assert_eq!(days.iter().copied().sum::<Duration>(), Duration::hours(7));
+
+        let month = Period::containing(PeriodKind::Month, wednesday, &calendar);
+        assert_eq!(
+            month.back(3).start,
+            NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()
+        );
+        assert_eq!(month.days().count(), 31);
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    Up,
+    Down,
+    #[default]
+    Nearest,
+}
+
+/// Whether rounding applies to every session on its own or only to the
+/// summed total of a project (or invoice line).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingScope {
+    Session,
+    #[default]
+    Total,
+}
+
+/// Rounds durations to a fixed increment of minutes, e.g. 6 or 15.
+/// An increment of 0 leaves durations untouched. Rounding is only applied
+/// when reporting; stored session durations are always exact.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rounding {
+    pub mode: RoundingMode,
+    pub increment_minutes: u32,
+    pub scope: RoundingScope,
+}
+
+impl Rounding {
+    pub fn new(mode: RoundingMode, increment_minutes: u32) -> Self {
+        Self {
+            mode,
+            increment_minutes,
+            scope: RoundingScope::default(),
+        }
+    }
+
+    pub fn with_scope(mut self, scope: RoundingScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Round a single session's duration; a no-op for total-scoped rules.
+    pub fn apply_to_session(&self, duration: Duration) -> Duration {
+        match self.scope {
+            RoundingScope::Session => self.apply(duration),
+            RoundingScope::Total => duration,
+        }
+    }
+
+    /// Round a summed duration; a no-op for session-scoped rules, whose
+    /// sessions have already been rounded individually.
+    pub fn apply_to_total(&self, duration: Duration) -> Duration {
+        match self.scope {
+            RoundingScope::Session => duration,
+            RoundingScope::Total => self.apply(duration),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.increment_minutes > 0
+    }
+
+    pub fn apply(&self, duration: Duration) -> Duration {
+        if !self.is_enabled() {
+            return duration;
+        }
+
+        let increment = self.increment_minutes as i64 * 60;
+        let seconds = duration.num_seconds();
+        let steps = match self.mode {
+            RoundingMode::Up => (seconds + increment - 1).div_euclid(increment),
+            RoundingMode::Down => seconds.div_euclid(increment),
+            RoundingMode::Nearest => (seconds + increment / 2).div_euclid(increment),
+        };
+        Duration::seconds(steps * increment)
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            "nearest" => Ok(Self::Nearest),
+            other => Err(format!(
+                "unknown rounding mode '{}' (expected up, down or nearest)",
+                other
+            )),
+        }
+    }
+}
+
+impl FromStr for RoundingScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "session" => Ok(Self::Session),
+            "total" => Ok(Self::Total),
+            other => Err(format!(
+                "unknown rounding scope '{}' (expected session or total)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RoundingScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Session => "session",
+            Self::Total => "total",
+        })
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_enabled() {
+            return f.write_str("off");
+        }
+        write!(
+            f,
+            "{} to {} min (per {})",
+            self.mode, self.increment_minutes, self.scope
+        )
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Nearest => "nearest",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounding_modes() {
+        let d = Duration::minutes(7) + Duration::seconds(30);
+
+        assert_eq!(
+            Rounding::new(RoundingMode::Up, 6).apply(d),
+            Duration::minutes(12)
+        );
+        assert_eq!(
+            Rounding::new(RoundingMode::Down, 6).apply(d),
+            Duration::minutes(6)
+        );
+        assert_eq!(
+            Rounding::new(RoundingMode::Nearest, 15).apply(d),
+            Duration::minutes(15)
+        );
+        assert_eq!(
+            Rounding::new(RoundingMode::Nearest, 6).apply(d),
+            Duration::minutes(6)
+        );
+        assert_eq!(Rounding::default().apply(d), d);
+    }
+
+    #[test]
+    fn test_rounding_scope() {
+        let per_session = Rounding::new(RoundingMode::Up, 15).with_scope(RoundingScope::Session);
+        let per_total = Rounding::new(RoundingMode::Up, 15);
+        let sessions = [Duration::minutes(5), Duration::minutes(5)];
+
+        let session_total: Duration = sessions
+            .iter()
+            .map(|d| per_session.apply_to_session(*d))
+            .sum();
+        assert_eq!(
+            per_session.apply_to_total(session_total),
+            Duration::minutes(30)
+        );
+
+        let raw_total: Duration = sessions
+            .iter()
+            .map(|d| per_total.apply_to_session(*d))
+            .sum();
+        assert_eq!(per_total.apply_to_total(raw_total), Duration::minutes(15));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::calendar::{Calendar, clip_session};
+use crate::goals::is_completed_pomodoro;
+use crate::{TimerConfig, TimerType, WorkSession};
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use std::collections::BTreeMap;
+
+/// Completed and abandoned Pomodoros on one day.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DayPomodoros {
+    pub completed: u32,
+    pub abandoned: u32,
+}
+
+impl DayPomodoros {
+    pub fn completion_rate(&self) -> Option<f64> {
+        completion_rate(self.completed, self.abandoned)
+    }
+}
+
+fn completion_rate(completed: u32, abandoned: u32) -> Option<f64> {
+    let started = completed + abandoned;
+    (started > 0).then(|| completed as f64 / started as f64)
+}
+
+/// Pomodoro and focus metrics over a range of days.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PomodoroStats {
+    /// Work sessions that ran their full configured length.
+    pub completed: u32,
+    /// Work sessions stopped before their configured length.
+    pub abandoned: u32,
+    pub average_work: Option<Duration>,
+    pub average_break: Option<Duration>,
+    /// Pauses taken during Pomodoro work sessions.
+    pub interruptions: u32,
+    /// Work sessions paused at least once.
+    pub interrupted_sessions: u32,
+    /// Every day of the range, oldest first.
+    pub days: Vec<(NaiveDate, DayPomodoros)>,
+    /// Focused time (stopwatch and Pomodoro work) per local hour of day.
+    pub focus_by_hour: [Duration; 24],
+}
+
+impl PomodoroStats {
+    pub fn completion_rate(&self) -> Option<f64> {
+        completion_rate(self.completed, self.abandoned)
+    }
+
+    /// The hour of day (0-23) with the most focused time.
+    pub fn best_hour(&self) -> Option<u32> {
+        self.focus_by_hour
+            .iter()
+            .enumerate()
+            .filter(|(_, focus)| !focus.is_zero())
+            .max_by_key(|(_, focus)| **focus)
+            .map(|(hour, _)| hour as u32)
+    }
+}
+
+fn average(durations: &[Duration]) -> Option<Duration> {
+    (!durations.is_empty())
+        .then(|| durations.iter().copied().sum::<Duration>() / durations.len() as i32)
+}
+
+/// Split the time worked in a session between the local hours of day it
+/// overlaps.
+fn add_focus_by_hour(
+    session: &WorkSession,
+    calendar: &Calendar,
+    focus_by_hour: &mut [Duration; 24],
+) {
+    let Some(end) = session.end_time else {
+        return;
+    };
+    let mut from: DateTime<Utc> = session.start_time;
+    loop {
+        let local = calendar.local(from);
+        let hour_start = local
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(local);
+        // Always move forward, even across DST changes.
+        let to = calendar
+            .to_utc(hour_start + Duration::hours(1))
+            .max(from + Duration::minutes(1));
+        if let Some(part) = clip_session(session, Some(from), Some(to)) {
+            focus_by_hour[local.hour() as usize] += part.duration;
+        }
+        if to >= end {
+            break;
+        }
+        from = to;
+    }
+}
+
+/// Pomodoro statistics for sessions ending in `start..end` (calendar days).
+pub fn pomodoro_stats(
+    sessions: &[WorkSession],
+    config: &TimerConfig,
+    calendar: &Calendar,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> PomodoroStats {
+    let mut days: BTreeMap<NaiveDate, DayPomodoros> = start
+        .iter_days()
+        .take_while(|day| *day < end)
+        .map(|day| (day, DayPomodoros::default()))
+        .collect();
+    let mut work = Vec::new();
+    let mut breaks = Vec::new();
+    let mut interruptions = 0;
+    let mut interrupted_sessions = 0;
+    let mut focus_by_hour = [Duration::zero(); 24];
+
+    for session in sessions {
+        let Some(session_end) = session.end_time else {
+            continue;
+        };
+        let day = calendar.day_of(session_end);
+        if day < start || day >= end {
+            continue;
+        }
+
+        match session.session_type {
+            TimerType::PomodoroWork => {
+                work.push(session.duration);
+                interruptions += session.pause_count;
+                if session.pause_count > 0 {
+                    interrupted_sessions += 1;
+                }
+                let totals = days.entry(day).or_default();
+                if is_completed_pomodoro(session, config) {
+                    totals.completed += 1;
+                } else {
+                    totals.abandoned += 1;
+                }
+                add_focus_by_hour(session, calendar, &mut focus_by_hour);
+            }
+            TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak => {
+                breaks.push(session.duration);
+            }
+            TimerType::Stopwatch => add_focus_by_hour(session, calendar, &mut focus_by_hour),
+            TimerType::CustomTimer => {}
+        }
+    }
+
+    PomodoroStats {
+        completed: days.values().map(|d| d.completed).sum(),
+        abandoned: days.values().map(|d| d.abandoned).sum(),
+        average_work: average(&work),
+        average_break: average(&breaks),
+        interruptions,
+        interrupted_sessions,
+        days: days.into_iter().collect(),
+        focus_by_hour,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SessionBuilder;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_pomodoro_stats() {
+        let config = TimerConfig::default();
+        let calendar = Calendar::utc();
+        let session = |kind: TimerType, hour: u32, minutes: i64, pauses: u32| {
+            let start = Utc.with_ymd_and_hms(2026, 3, 2, hour, 0, 0).unwrap();
+            SessionBuilder::starting(start, Duration::minutes(minutes))
+                .kind(kind)
+                .pauses(pauses)
+                .build()
+        };
+        let sessions = vec![
+            session(TimerType::PomodoroWork, 9, 25, 0),
+            session(TimerType::PomodoroShortBreak, 9, 5, 0),
+            session(TimerType::PomodoroWork, 10, 25, 2),
+            session(TimerType::PomodoroWork, 11, 10, 1),
+            session(TimerType::PomodoroLongBreak, 11, 15, 0),
+            // An hour and a half spanning 14:00 and 15:00.
+            session(TimerType::Stopwatch, 14, 90, 0),
+        ];
+
+        let day = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let stats = pomodoro_stats(&sessions, &config, &calendar, day, day + Duration::days(1));
+        assert_eq!((stats.completed, stats.abandoned), (2, 1));
+        assert_eq!(stats.average_work, Some(Duration::minutes(20)));
+        assert_eq!(stats.average_break, Some(Duration::minutes(10)));
+        assert_eq!((stats.interruptions, stats.interrupted_sessions), (3, 2));
+        assert_eq!(
+            stats.days,
+            vec![(
+                day,
+                DayPomodoros {
+                    completed: 2,
+                    abandoned: 1
+                }
+            )]
+        );
+        assert_eq!(stats.focus_by_hour[15], Duration::minutes(30));
+        assert_eq!(stats.best_hour(), Some(14));
+    }
+}
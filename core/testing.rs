@@ -0,0 +1,107 @@
+use crate::{NetupiCore, PersistenceManager, TimerType, WorkSession};
+use chrono::{DateTime, Duration, Utc};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A core keeping its data in a new temporary directory, which is removed
+/// when the returned `TempDir` is dropped.
+pub(crate) async fn test_core() -> (TempDir, NetupiCore) {
+    let dir = tempfile::tempdir().unwrap();
+    let core = core_in(dir.path()).await;
+    (dir, core)
+}
+
+/// Another core on the data in `dir`, the way a second `netupi` process
+/// sees it.
+pub(crate) async fn core_in(dir: &Path) -> NetupiCore {
+    let persistence = PersistenceManager::with_data_dir(dir.to_path_buf()).unwrap();
+    NetupiCore::with_persistence(persistence).await.unwrap()
+}
+
+/// Builds sessions for tests: a finished stopwatch session without tags,
+/// description or billing overrides, changed with the methods below.
+pub(crate) struct SessionBuilder(WorkSession);
+
+impl SessionBuilder {
+    /// A session of `duration` that started at `start`.
+    pub(crate) fn starting(start: DateTime<Utc>, duration: Duration) -> Self {
+        Self(WorkSession {
+            id: uuid::Uuid::new_v4(),
+            session_type: TimerType::Stopwatch,
+            start_time: start,
+            end_time: Some(start + duration),
+            duration,
+            paused_duration: Duration::zero(),
+            description: None,
+            tags: Vec::new(),
+            billable: None,
+            hourly_rate: None,
+            invoice: None,
+            pause_count: 0,
+            target_duration: None,
+        })
+    }
+
+    /// A session of `duration` that ended at `end`.
+    pub(crate) fn ending(end: DateTime<Utc>, duration: Duration) -> Self {
+        Self::starting(end - duration, duration)
+    }
+
+    pub(crate) fn kind(mut self, session_type: TimerType) -> Self {
+        self.0.session_type = session_type;
+        self
+    }
+
+    /// Track the session against `project`, its first tag.
+    pub(crate) fn project(self, project: &str) -> Self {
+        self.tags(&[project])
+    }
+
+    pub(crate) fn tags(mut self, tags: &[&str]) -> Self {
+        self.0.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    pub(crate) fn description(mut self, description: &str) -> Self {
+        self.0.description = Some(description.to_string());
+        self
+    }
+
+    /// Count `paused` of the time between start and end as paused instead
+    /// of worked.
+    pub(crate) fn paused(mut self, paused: Duration) -> Self {
+        self.0.duration -= paused - self.0.paused_duration;
+        self.0.paused_duration = paused;
+        self
+    }
+
+    pub(crate) fn pauses(mut self, count: u32) -> Self {
+        self.0.pause_count = count;
+        self
+    }
+
+    pub(crate) fn billable(mut self, billable: bool) -> Self {
+        self.0.billable = Some(billable);
+        self
+    }
+
+    pub(crate) fn rate(mut self, hourly_rate: f64) -> Self {
+        self.0.hourly_rate = Some(hourly_rate);
+        self
+    }
+
+    pub(crate) fn invoice(mut self, number: &str) -> Self {
+        self.0.invoice = Some(number.to_string());
+        self
+    }
+
+    /// Leave the session unfinished, as the timer sees it while running.
+    pub(crate) fn running(mut self) -> Self {
+        self.0.end_time = None;
+        self
+    }
+
+    pub(crate) fn build(self) -> WorkSession {
+        self.0
+    }
+}
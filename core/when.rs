@@ -0,0 +1,242 @@
+use crate::calendar::Calendar;
+use crate::duration::parse_duration;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+
+/// Parse a point in time written the way people say it, as of `now` in
+/// `calendar`'s time zone:
+///
+/// - `now`, or an offset into the past: `10 minutes ago`, `1h30m ago`,
+///   `3 days ago`, or just `2w`
+/// - a clock time on the current day: `17:30`, `9:05:30`, `5pm`, `5:30pm`
+/// - a day, which means its start: `today`, `yesterday`, `monday` (the
+///   latest one, today included), `last monday` (the one before today),
+///   `this week`, `last week` or an ISO date like `2026-10-01`
+/// - a day and a clock time: `yesterday 17:30`, `monday 9am`,
+///   `2026-10-01 14:00` or `2026-10-01T14:00`
+///
+/// Days follow the calendar's day start: with days starting at 04:00,
+/// `01:30` is the early hours after the current day's evening.
+pub fn parse_instant(
+    input: &str,
+    now: DateTime<Utc>,
+    calendar: &Calendar,
+) -> Result<DateTime<Utc>, String> {
+    let text = input.trim().to_lowercase();
+    let invalid = || {
+        format!(
+            "invalid time '{}' (expected e.g. 17:30, 10 minutes ago, yesterday, \
+             last monday 9am or 2026-10-01)",
+            input.trim()
+        )
+    };
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    if text == "now" {
+        return Ok(now);
+    }
+    if let Some(offset) = text.strip_suffix("ago") {
+        return parse_offset(offset.trim())
+            .and_then(|offset| now.checked_sub_signed(offset))
+            .ok_or_else(invalid);
+    }
+    if let Some(offset) = parse_offset(&text).filter(|_| has_unit(&text)) {
+        return now.checked_sub_signed(offset).ok_or_else(invalid);
+    }
+
+    let today = calendar.day_of(now);
+    if let Some(time) = parse_clock(&text) {
+        return Ok(at_time(calendar, today, time));
+    }
+    if let Some(day) = parse_day_words(&text, today, calendar) {
+        return Ok(calendar.day_start(day));
+    }
+    // A day followed by a clock time, split at the last space or at a 'T'.
+    let split = text
+        .rsplit_once(' ')
+        .or_else(|| text.split_once('t').filter(|(day, _)| day.contains('-')));
+    if let Some((day, time)) = split
+        && let Some(day) = parse_day_words(day.trim(), today, calendar)
+        && let Some(time) = parse_clock(time)
+    {
+        return Ok(at_time(calendar, day, time));
+    }
+    Err(invalid())
+}
+
+/// Parse a day the way [`parse_instant`] does, e.g. `yesterday`,
+/// `last monday`, `2026-10-01` or `2w` (two weeks ago).
+pub fn parse_day(
+    input: &str,
+    now: DateTime<Utc>,
+    calendar: &Calendar,
+) -> Result<NaiveDate, String> {
+    parse_instant(input, now, calendar).map(|instant| calendar.day_of(instant))
+}
+
+/// When `time` occurs on `day`, which may be the next date for times
+/// before the calendar's day start.
+fn at_time(calendar: &Calendar, day: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let instant = calendar.to_utc(day.and_time(time));
+    if instant < calendar.day_start(day) {
+        return calendar.to_utc((day + Duration::days(1)).and_time(time));
+    }
+    instant
+}
+
+/// `today`, `yesterday`, `tomorrow`, weekday names with an optional `this`
+/// or `last`, `this week`, `last week` and ISO dates.
+fn parse_day_words(text: &str, today: NaiveDate, calendar: &Calendar) -> Option<NaiveDate> {
+    match text {
+        "today" => return Some(today),
+        "yesterday" => return Some(today - Duration::days(1)),
+        "tomorrow" => return Some(today + Duration::days(1)),
+        "this week" => return Some(calendar.week_of(today)),
+        "last week" => return Some(calendar.week_of(today) - Duration::weeks(1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let (last, name) = match text.split_once(' ') {
+        Some(("last", name)) => (true, name),
+        Some(("this", name)) => (false, name),
+        Some(_) => return None,
+        None => (false, text),
+    };
+    let weekday: Weekday = name.parse().ok()?;
+    let days_back =
+        (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    let days_back = if last && days_back == 0 { 7 } else { days_back };
+    Some(today - Duration::days(days_back as i64))
+}
+
+/// `17:30`, `17:30:15`, `5pm`, `5:30pm`, `12am`.
+fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let (clock, pm) = match (text.strip_suffix("am"), text.strip_suffix("pm")) {
+        (Some(clock), _) => (clock.trim(), Some(false)),
+        (_, Some(clock)) => (clock.trim(), Some(true)),
+        _ => (text, None),
+    };
+    let Some(pm) = pm else {
+        return NaiveTime::parse_from_str(clock, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(clock, "%H:%M:%S"))
+            .ok();
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    NaiveTime::from_hms_opt(hour % 12 + if pm { 12 } else { 0 }, minute, 0)
+}
+
+/// Whether `text` names a unit, so a bare `17` isn't taken as 17 minutes.
+fn has_unit(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+/// A duration like [`parse_duration`] reads, or a number of days or weeks.
+fn parse_offset(text: &str) -> Option<Duration> {
+    let number_len = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let days_per_unit = match text[number_len..].trim() {
+        "d" | "day" | "days" => Some(1),
+        "w" | "wk" | "week" | "weeks" => Some(7),
+        _ => None,
+    };
+    match days_per_unit {
+        Some(days) => {
+            let number: i64 = text[..number_len].parse().ok()?;
+            Duration::try_days(number.checked_mul(days)?)
+        }
+        None => parse_duration(text).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::CalendarConfig;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_instant() {
+        let calendar = Calendar::utc();
+        // A Wednesday.
+        let now = Utc.with_ymd_and_hms(2026, 3, 4, 15, 0, 0).unwrap();
+        let at = |day: u32, hour: u32, minute: u32| {
+            Ok(Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap())
+        };
+        let parse = |text: &str| parse_instant(text, now, &calendar);
+
+        assert_eq!(parse("now"), Ok(now));
+        assert_eq!(parse("10 minutes ago"), at(4, 14, 50));
+        assert_eq!(parse("1h30m ago"), at(4, 13, 30));
+        assert_eq!(parse("2 days ago"), at(2, 15, 0));
+        assert_eq!(parse("1w"), Ok(now - Duration::weeks(1)));
+        assert_eq!(parse("17:30"), at(4, 17, 30));
+        assert_eq!(parse("5:15pm"), at(4, 17, 15));
+        assert_eq!(parse("12am"), at(4, 0, 0));
+        assert_eq!(parse("Yesterday"), at(3, 0, 0));
+        assert_eq!(parse("yesterday 9am"), at(3, 9, 0));
+        assert_eq!(parse("wednesday"), at(4, 0, 0));
+        assert_eq!(
+            parse("last wednesday"),
+            Ok(Utc.with_ymd_and_hms(2026, 2, 25, 0, 0, 0).unwrap())
+        );
+        assert_eq!(parse("last monday 9:30"), at(2, 9, 30));
+        assert_eq!(parse("this week"), at(2, 0, 0));
+        assert_eq!(parse("2026-03-01"), at(1, 0, 0));
+        assert_eq!(parse("2026-03-01t08:00"), at(1, 8, 0));
+        assert_eq!(parse("2026-03-01 8pm"), at(1, 20, 0));
+
+        for text in [
+            "",
+            "17",
+            "soon",
+            "13pm",
+            "last 17:30",
+            "25:00",
+            "monday at 9",
+            "99999999999 weeks ago",
+            "9999999999999999999 days ago",
+            "99999999999999h ago",
+            "99999999999w",
+        ] {
+            assert!(parse(text).is_err(), "{text} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_parse_instant_follows_day_start() {
+        let calendar = Calendar::from_config(&CalendarConfig {
+            timezone: Some("Europe/Madrid".to_string()),
+            day_starts_at: NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+            week_starts_on: Weekday::Mon,
+        })
+        .unwrap();
+        // 01:00 on the 5th in Madrid, still the 4th for a 04:00 day start.
+        let now = Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_instant("23:30", now, &calendar),
+            Ok(Utc.with_ymd_and_hms(2026, 3, 4, 22, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_instant("00:45", now, &calendar),
+            Ok(Utc.with_ymd_and_hms(2026, 3, 4, 23, 45, 0).unwrap())
+        );
+        assert_eq!(
+            parse_day("today", now, &calendar),
+            Ok(NaiveDate::from_ymd_opt(2026, 3, 4).unwrap())
+        );
+        assert_eq!(
+            parse_day("2w", now, &calendar),
+            Ok(NaiveDate::from_ymd_opt(2026, 2, 18).unwrap())
+        );
+    }
+}
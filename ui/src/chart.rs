@@ -0,0 +1,185 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use crossterm::style::{Color, Stylize};
+use netupi_core::period::PeriodSummary;
+
+/// Colors cycled through for the projects of a bar chart.
+const PROJECT_COLORS: [Color; 8] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+    Color::DarkCyan,
+    Color::DarkYellow,
+];
+
+/// Heatmap cell colors from "nothing tracked" to "busiest days" (256-color
+/// greens, like a GitHub contribution graph).
+const HEAT_COLORS: [Color; 5] = [
+    Color::AnsiValue(238),
+    Color::AnsiValue(22),
+    Color::AnsiValue(28),
+    Color::AnsiValue(34),
+    Color::AnsiValue(40),
+];
+
+fn hours(duration: Duration) -> f64 {
+    duration.num_minutes() as f64 / 60.0
+}
+
+/// Bar lengths for durations stacked on one row. Lengths are rounded on the
+/// running total so the segments add up to the rounded total.
+fn segment_lengths(durations: &[Duration], chars_per_second: f64) -> Vec<usize> {
+    let mut cumulative = Duration::zero();
+    let mut drawn = 0;
+    durations
+        .iter()
+        .map(|duration| {
+            cumulative += *duration;
+            let end = (cumulative.num_seconds() as f64 * chars_per_second).round() as usize;
+            let length = end - drawn;
+            drawn = end;
+            length
+        })
+        .collect()
+}
+
+/// Horizontal bars of hours per day, stacked and colored by project,
+/// followed by one bar per project for the whole period.
+pub fn bar_chart(summary: &PeriodSummary, width: usize) -> Vec<String> {
+    let colors: Vec<Color> = (0..summary.projects.len())
+        .map(|i| PROJECT_COLORS[i % PROJECT_COLORS.len()])
+        .collect();
+    let mut lines = Vec::new();
+
+    let daily = summary.daily_totals();
+    let busiest = daily.iter().copied().max().unwrap_or_default();
+    let scale = if busiest.is_zero() {
+        0.0
+    } else {
+        width as f64 / busiest.num_seconds() as f64
+    };
+    for (index, (day, total)) in summary.period.days().zip(&daily).enumerate() {
+        let parts: Vec<Duration> = summary.projects.iter().map(|p| p.per_day[index]).collect();
+        let bar: String = segment_lengths(&parts, scale)
+            .into_iter()
+            .zip(&colors)
+            .filter(|(length, _)| *length > 0)
+            .map(|(length, color)| "█".repeat(length).with(*color).to_string())
+            .collect();
+        let label = if total.is_zero() {
+            String::new()
+        } else {
+            format!(" {:.1}h", hours(*total))
+        };
+        lines.push(format!("{} │{}{}", day.format("%a %m-%d"), bar, label));
+    }
+
+    if summary.projects.is_empty() {
+        return lines;
+    }
+
+    lines.push(String::new());
+    let name_width = summary
+        .projects
+        .iter()
+        .map(|p| p.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(20);
+    let longest = summary
+        .projects
+        .iter()
+        .map(|p| p.total)
+        .max()
+        .unwrap_or_default();
+    for (project, color) in summary.projects.iter().zip(&colors) {
+        let length = if longest.is_zero() {
+            0
+        } else {
+            (project.total.num_seconds() as f64 / longest.num_seconds() as f64 * width as f64)
+                .round() as usize
+        };
+        let name: String = project.name.chars().take(name_width).collect();
+        lines.push(format!(
+            "{:<name_width$} │{} {:.1}h",
+            name,
+            "█".repeat(length).with(*color),
+            hours(project.total)
+        ));
+    }
+    lines
+}
+
+fn heat_level(duration: Duration, busiest: Duration) -> usize {
+    if duration.is_zero() || busiest.is_zero() {
+        return 0;
+    }
+    let fraction = duration.num_seconds() as f64 / busiest.num_seconds() as f64;
+    match fraction {
+        f if f <= 0.25 => 1,
+        f if f <= 0.5 => 2,
+        f if f <= 0.75 => 3,
+        _ => 4,
+    }
+}
+
+/// A calendar heatmap with one column per week and one row per weekday.
+/// `start` must be the first day of a week and `totals` holds one entry per
+/// day from `start` up to and including today.
+pub fn heatmap(start: NaiveDate, totals: &[Duration]) -> Vec<String> {
+    let weeks = totals.len().div_ceil(7);
+    let busiest = totals.iter().copied().max().unwrap_or_default();
+
+    // Month names above the first week containing the 1st of the month.
+    let mut header = String::from("    ");
+    let mut column = 0;
+    for week in 0..weeks {
+        let first = start + Duration::weeks(week as i64);
+        let month_start = (0..7)
+            .map(|d| first + Duration::days(d))
+            .find(|day| day.day() == 1);
+        if let Some(day) = month_start
+            && week * 2 >= column
+        {
+            header.push_str(&" ".repeat(week * 2 - column));
+            let name = day.format("%b").to_string();
+            column = week * 2 + name.len();
+            header.push_str(&name);
+        }
+    }
+    let mut lines = vec![header];
+
+    for weekday in 0..7 {
+        let label = if weekday % 2 == 1 {
+            (start + Duration::days(weekday as i64))
+                .format("%a")
+                .to_string()
+        } else {
+            String::new()
+        };
+        let cells: String = (0..weeks)
+            .map(|week| match totals.get(week * 7 + weekday) {
+                Some(total) => format!("{} ", "■".with(HEAT_COLORS[heat_level(*total, busiest)])),
+                None => "  ".to_string(),
+            })
+            .collect();
+        lines.push(format!("{:<4}{}", label, cells));
+    }
+
+    let legend: String = HEAT_COLORS
+        .iter()
+        .map(|color| format!("{} ", "■".with(*color)))
+        .collect();
+    let total: Duration = totals.iter().copied().sum();
+    let active_days = totals.iter().filter(|d| !d.is_zero()).count();
+    lines.push(String::new());
+    lines.push(format!(
+        "    Less {}More   {:.1} hours tracked on {} days",
+        legend,
+        hours(total),
+        active_days
+    ));
+    lines
+}
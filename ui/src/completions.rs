@@ -0,0 +1,72 @@
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Zsh};
+use netupi_core::NetupiCore;
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+
+/// Environment variable that asks `netupi` for completions instead of
+/// running a command. The scripts from `netupi completions` set it.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+}
+
+impl CompletionShell {
+    fn completer(self) -> &'static dyn EnvCompleter {
+        match self {
+            Self::Bash => &Bash,
+            Self::Zsh => &Zsh,
+            Self::Fish => &Fish,
+            Self::Elvish => &Elvish,
+        }
+    }
+}
+
+/// Print the script that registers completions with `shell`. The script
+/// calls back into `netupi`, so project names are always current.
+pub fn print_completions(shell: CompletionShell) -> io::Result<()> {
+    shell.completer().write_registration(
+        COMPLETE_VAR,
+        "netupi",
+        "netupi",
+        "netupi",
+        &mut io::stdout(),
+    )
+}
+
+/// Print the man page for `command` in roff, or write it and one page per
+/// subcommand to `out_dir`.
+pub fn man_pages(command: clap::Command, out_dir: Option<&Path>) -> io::Result<()> {
+    let command = command.version(env!("CARGO_PKG_VERSION"));
+    match out_dir {
+        Some(out_dir) => {
+            std::fs::create_dir_all(out_dir)?;
+            clap_mangen::generate_to(command, out_dir)
+        }
+        None => clap_mangen::Man::new(command).render(&mut io::stdout()),
+    }
+}
+
+/// Projects with tracked time starting with `current`.
+pub fn complete_project(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    // Completers are synchronous but run inside main's runtime.
+    let projects = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current()
+            .block_on(async { NetupiCore::new().await?.get_projects().await })
+    })
+    .unwrap_or_default();
+    projects
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(current))
+        .map(|(name, _)| CompletionCandidate::new(name))
+        .collect()
+}
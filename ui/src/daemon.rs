@@ -0,0 +1,211 @@
+use crate::display::{
+    continued_message, format_budget_status, format_clock, length_label, status_lines,
+};
+use crate::interactive::PrintSink;
+use crate::{Commands, parse_time_arg, print_paused, print_resumed, print_stopped};
+use netupi_core::ipc::{self, Client};
+use netupi_core::notify::{self, Notifier};
+use netupi_core::{
+    NetupiCore, PersistenceError, PersistenceManager, StartRequest, TimerStatus, TimerType,
+};
+use std::sync::Arc;
+use tokio::signal::unix::{SignalKind, signal};
+
+/// Serve the timer to other `netupi` commands until Ctrl+C or SIGTERM,
+/// printing alerts as they happen besides the usual notifications.
+pub async fn run(core: NetupiCore) -> Result<(), PersistenceError> {
+    let path = PersistenceManager::new()?.socket_file();
+    let mut sinks = notify::default_sinks();
+    sinks.push(Arc::new(PrintSink::stdout()));
+    let notifier = Notifier::spawn(&core, sinks);
+
+    println!("🛰️  Daemon listening on {}", path.display());
+    println!("Timer commands now return immediately. Press Ctrl+C to stop the daemon.");
+    ipc::serve(core, &path, shutdown_signal()).await?;
+    notifier.finish().await;
+    println!("\n👋 Daemon stopped. The running session, if any, was saved.");
+    Ok(())
+}
+
+/// Completes on Ctrl+C or SIGTERM.
+pub async fn shutdown_signal() {
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+        }
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+/// Run a timer command through a running daemon. Returns `false` when the
+/// command isn't a timer command or no daemon is listening, in which case
+/// the caller runs it in this process.
+pub async fn run_remote(command: &Commands) -> Result<bool, PersistenceError> {
+    let request = start_request(command);
+    if request.is_none()
+        && !matches!(
+            command,
+            Commands::Continue { .. }
+                | Commands::Stop { .. }
+                | Commands::Status { .. }
+                | Commands::Pause
+                | Commands::Resume
+        )
+    {
+        return Ok(false);
+    }
+    let path = PersistenceManager::new()?.socket_file();
+    let Ok(mut client) = Client::connect(&path).await else {
+        return Ok(false);
+    };
+
+    if let Some((request, at)) = request {
+        let status = match parse_time_arg(at).await? {
+            Some(at) => client.start_at(request, at).await?,
+            None => client.start(request).await?,
+        };
+        print_started(&status);
+        println!("🛰️  Running in the daemon. Use 'netupi status' or 'netupi stop'.");
+        return Ok(true);
+    }
+    match command {
+        Commands::Continue { n } => {
+            let (extended, status) = client.continue_session(*n as usize).await?;
+            println!("{}", continued_message(extended, &status));
+            println!("🛰️  Running in the daemon. Use 'netupi status' or 'netupi stop'.");
+        }
+        Commands::Stop { at } => {
+            let stopped = match parse_time_arg(at.as_deref()).await? {
+                Some(at) => client.stop_at(at).await?,
+                None => client.stop().await?,
+            };
+            match stopped {
+                Some(status) => print_stopped(status.timer.elapsed),
+                None => println!("⏸️  No timer is currently running."),
+            }
+        }
+        Commands::Status { .. } => {
+            for line in status_lines(&client.status().await?) {
+                println!("{}", line);
+            }
+        }
+        Commands::Pause => print_paused(client.pause().await?),
+        Commands::Resume => print_resumed(client.resume().await?),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// The timer `command` starts, and when if given with `--at`.
+fn start_request(command: &Commands) -> Option<(StartRequest, Option<&str>)> {
+    Some(match command {
+        Commands::Work {
+            project,
+            description,
+            preset,
+            at,
+        } => (
+            StartRequest::Pomodoro {
+                project: project.clone(),
+                description: description.clone(),
+                preset: preset.clone(),
+            },
+            at.as_deref(),
+        ),
+        Commands::Break => (StartRequest::ShortBreak, None),
+        Commands::LongBreak => (StartRequest::LongBreak, None),
+        Commands::Stopwatch => (StartRequest::Stopwatch, None),
+        Commands::Timer { duration, label } => (
+            StartRequest::Countdown {
+                duration: *duration,
+                label: (!label.is_empty()).then(|| label.join(" ")),
+            },
+            None,
+        ),
+        Commands::TimeTrack {
+            project,
+            description,
+            billable,
+            non_billable,
+            rate,
+            at,
+        } => (
+            StartRequest::Project {
+                project: project.clone(),
+                description: description.clone(),
+                billable: if *billable {
+                    Some(true)
+                } else if *non_billable {
+                    Some(false)
+                } else {
+                    None
+                },
+                rate: *rate,
+            },
+            at.as_deref(),
+        ),
+        _ => return None,
+    })
+}
+
+fn print_started(status: &TimerStatus) {
+    let length = status.timer.target_duration;
+    let project = status.session.as_ref().and_then(|s| s.tags.first());
+    match (status.timer.timer_type, project) {
+        (TimerType::Stopwatch, Some(project)) => {
+            println!("🚀 Started work session for project: {}", project)
+        }
+        (TimerType::Stopwatch, None) => println!("⏱️  Stopwatch started"),
+        (TimerType::CustomTimer, _) => {
+            match status.session.as_ref().and_then(|s| s.description.as_ref()) {
+                Some(label) => println!(
+                    "⏳ Started {} timer: {}",
+                    length.map(format_clock).unwrap_or_default(),
+                    label
+                ),
+                None => println!(
+                    "⏳ Started {} timer",
+                    length.map(format_clock).unwrap_or_default()
+                ),
+            }
+        }
+        (timer_type, project) => {
+            let (icon, name) = match timer_type {
+                TimerType::PomodoroWork => ("🍅", "Pomodoro"),
+                TimerType::PomodoroShortBreak => ("☕", "short break"),
+                _ => ("☕", "long break"),
+            };
+            println!(
+                "{} Started {} {}",
+                icon,
+                length.map(length_label).unwrap_or_default(),
+                name
+            );
+            if let Some(project) = project {
+                println!("🏗️  Project: {}", project);
+            }
+        }
+    }
+
+    if let Some(preset) = &status.cycle_preset {
+        println!("🎛️  Preset: {}", preset);
+    }
+    if let Some(session) = &status.session {
+        match session.billable {
+            Some(true) => println!("💵 Billable session"),
+            Some(false) => println!("🆓 Non-billable session"),
+            None => {}
+        }
+        if let Some(rate) = session.hourly_rate {
+            println!("💵 Rate: {:.2}/h", rate);
+        }
+    }
+    if let Some(budget) = &status.budget {
+        println!("📊 Budget: {}", format_budget_status(budget));
+    }
+}
@@ -0,0 +1,524 @@
+use chrono::{Datelike, Duration};
+use netupi_core::budget::{BudgetPeriod, BudgetStatus};
+use netupi_core::calendar::CalendarConfig;
+use netupi_core::config::DisplayConfig;
+use netupi_core::duration;
+use netupi_core::goals::{GoalKind, GoalProgress};
+use netupi_core::notify::{Notification, NotificationKind};
+use netupi_core::period::{PeriodKind, PeriodSummary};
+use netupi_core::stats::PomodoroStats;
+use netupi_core::{ProjectSettings, ProjectSummary, TimerConfig, TimerStatus, TimerType};
+
+/// Turn colored output on or off as configured. `NO_COLOR` still wins when
+/// colors are on.
+pub fn apply_display_config(config: &DisplayConfig) {
+    let no_color = std::env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+    crossterm::style::force_color_output(config.color && !no_color);
+}
+
+/// Format a duration as "X hours Y minutes" (or "Y minutes" under an hour).
+pub fn format_duration(duration: Duration) -> String {
+    let total_minutes = duration.num_minutes();
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+    if hours > 0 {
+        format!("{} hours {} minutes", hours, minutes)
+    } else {
+        format!("{} minutes", minutes)
+    }
+}
+
+pub fn format_money(amount: f64, currency: &str) -> String {
+    format!("{:.2} {}", amount, currency)
+}
+
+/// Billable time and earnings appended to a project line, empty when nothing
+/// in the summary is billable.
+pub fn billing_suffix(summary: &ProjectSummary) -> String {
+    if summary.billable.is_zero() {
+        return String::new();
+    }
+    format!(
+        " | 💵 billable {} → {}",
+        format_duration(summary.billable),
+        format_money(summary.earnings, &summary.currency)
+    )
+}
+
+/// Reported time for a summary: the rounded total, followed by the exact
+/// total when rounding changed it.
+pub fn format_summary_time(summary: &ProjectSummary) -> String {
+    format_rounded(summary.rounded, summary.total)
+}
+
+pub fn format_rounded(rounded: Duration, exact: Duration) -> String {
+    if rounded == exact {
+        format_duration(exact)
+    } else {
+        format!(
+            "{} (exact: {})",
+            format_duration(rounded),
+            format_duration(exact)
+        )
+    }
+}
+
+/// Rounded time, rate, billable time and earnings lines for a project's
+/// detail view. Billing lines are omitted when the project is not billable
+/// and no session was billed.
+pub fn project_billing_lines(summary: &ProjectSummary, settings: &ProjectSettings) -> Vec<String> {
+    let mut lines = Vec::new();
+    if summary.rounded != summary.total {
+        lines.push(format!(
+            "Rounded time: {}",
+            format_duration(summary.rounded)
+        ));
+    }
+    if summary.billable.is_zero() && !settings.billable {
+        return lines;
+    }
+
+    if let Some(rate) = settings.hourly_rate {
+        lines.push(format!("Rate: {}/h", format_money(rate, &summary.currency)));
+    }
+    lines.push(format!(
+        "Billable time: {}",
+        format_duration(summary.billable)
+    ));
+    lines.push(format!(
+        "Earnings: {}",
+        format_money(summary.earnings, &summary.currency)
+    ));
+    lines
+}
+
+/// One-line budget usage, e.g. "32 hours 0 minutes of 40 hours 0 minutes
+/// (80%), 8 hours 0 minutes remaining".
+pub fn format_budget_status(status: &BudgetStatus) -> String {
+    let period = match status.budget.period {
+        BudgetPeriod::Total => "",
+        BudgetPeriod::Weekly => " this week",
+    };
+    format!(
+        "{} of {}{} ({:.0}%), {} remaining",
+        format_duration(status.consumed),
+        format_duration(status.budget.limit),
+        period,
+        status.percent(),
+        format_duration(status.remaining())
+    )
+}
+
+pub fn budget_alert(project: &str, percent: u8) -> String {
+    if percent >= 100 {
+        format!("🚨 Budget exhausted for '{}' ({}% used)", project, percent)
+    } else {
+        format!("⚠️  '{}' has used {}% of its budget", project, percent)
+    }
+}
+
+/// A timer length as an adjective, e.g. "25-minute" (or "90s" for lengths
+/// that aren't whole minutes).
+pub fn length_label(length: Duration) -> String {
+    if length.num_seconds() % 60 == 0 {
+        format!("{}-minute", length.num_minutes())
+    } else {
+        duration::format_duration(length)
+    }
+}
+
+/// Every setting as "name = value", aligned, with its description.
+pub fn config_lines(config: &TimerConfig) -> Vec<String> {
+    let entries = config.entries();
+    let width = entries
+        .iter()
+        .map(|(key, value)| key.name.len() + value.len())
+        .max()
+        .unwrap_or(0);
+    entries
+        .into_iter()
+        .map(|(key, value)| {
+            let padding = width - key.name.len() - value.len();
+            format!(
+                "{} = {}{}  # {}",
+                key.name,
+                value,
+                " ".repeat(padding),
+                key.description
+            )
+        })
+        .collect()
+}
+
+/// Every preset as "name  25m work, 5m break, 15m long break every 4", with
+/// the one matching the current timer settings marked.
+pub fn preset_lines(config: &TimerConfig) -> Vec<String> {
+    let active = config.active_preset();
+    let width = config.presets.keys().map(String::len).max().unwrap_or(0);
+    config
+        .presets
+        .iter()
+        .map(|(name, preset)| {
+            format!(
+                "{} {:width$}  {} work, {} break, {} long break every {}{}",
+                if active == Some(name.as_str()) {
+                    "▶"
+                } else {
+                    " "
+                },
+                name,
+                duration::format_duration(preset.work),
+                duration::format_duration(preset.short_break),
+                duration::format_duration(preset.long_break),
+                preset.long_break_interval,
+                if preset.auto_start_breaks || preset.auto_start_work {
+                    " (auto-start)"
+                } else {
+                    ""
+                },
+                width = width
+            )
+        })
+        .collect()
+}
+
+/// The running timer as shown by `status`.
+pub fn status_lines(status: &TimerStatus) -> Vec<String> {
+    let timer = &status.timer;
+    let mut lines = vec![
+        format!("Timer Type: {:?}", timer.timer_type),
+        format!("State: {:?}", timer.state),
+        format!(
+            "Elapsed: {:02}:{:02}",
+            timer.elapsed.num_minutes(),
+            timer.elapsed.num_seconds() % 60
+        ),
+    ];
+    if let Some(remaining) = timer.remaining() {
+        lines.push(format!("Remaining: {}", format_clock(remaining)));
+    }
+    if let Some(preset) = &status.cycle_preset {
+        lines.push(format!("Preset: {}", preset));
+    }
+    if let Some(project) = status.session.as_ref().and_then(|s| s.tags.first()) {
+        lines.push(format!("Project: {}", project));
+    }
+    if let Some(budget) = &status.budget {
+        lines.push(format!("Budget: {}", format_budget_status(budget)));
+    }
+    lines
+}
+
+/// Message shown when a timer reaches its target duration.
+pub fn completion_message(timer_type: TimerType, label: Option<&str>) -> String {
+    match (timer_type, label) {
+        (TimerType::PomodoroWork, _) => "🍅 Pomodoro complete! Time for a break.".to_string(),
+        (TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak, _) => {
+            "☕ Break is over! Ready for the next Pomodoro?".to_string()
+        }
+        (_, Some(label)) => format!("🔔 Time's up: {}", label),
+        (_, None) => "🔔 Time's up!".to_string(),
+    }
+}
+
+/// What `continue` did, e.g. "↩️  Continuing api: login screen".
+pub fn continued_message(extended: bool, status: &TimerStatus) -> String {
+    let session = status.session.as_ref();
+    let mut what = session
+        .and_then(|s| s.project())
+        .unwrap_or("your last session")
+        .to_string();
+    if let Some(description) = session.and_then(|s| s.description.as_deref()) {
+        what.push_str(&format!(": {}", description));
+    }
+    if extended {
+        format!(
+            "↩️  Picked up where you left off on {} ({} so far)",
+            what,
+            format_duration(status.timer.elapsed)
+        )
+    } else {
+        format!("↩️  Continuing {}", what)
+    }
+}
+
+/// A notification as one line, e.g. "🍅 Pomodoro complete! Time for a break."
+pub fn notification_message(notification: &Notification) -> String {
+    let (title, body) = (&notification.title, &notification.body);
+    match notification.kind {
+        NotificationKind::Pomodoro => format!("🍅 {}! {}", title, body),
+        NotificationKind::Break => format!("☕ {}! {}", title, body),
+        NotificationKind::Countdown if body.is_empty() => format!("🔔 {}!", title),
+        NotificationKind::Countdown => format!("🔔 {}: {}", title, body),
+        NotificationKind::Budget => format!("⚠️  {}: {}", title, body),
+    }
+}
+
+/// A clock-style "MM:SS" (or "H:MM:SS") duration for countdowns. Partial
+/// seconds round up, so a countdown only shows 00:00 once it's done.
+pub fn format_clock(duration: Duration) -> String {
+    let seconds = (duration.num_milliseconds().max(0) + 999) / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// A fixed-width text progress bar, e.g. "[██████░░░░]  60%".
+pub fn progress_bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0)) * width as f64).round() as usize;
+    format!(
+        "[{}{}] {:>3.0}%",
+        "█".repeat(filled),
+        "░".repeat(width - filled),
+        fraction * 100.0
+    )
+}
+
+/// Progress bar and streak lines for a goal.
+pub fn goal_lines(goal: &GoalProgress) -> Vec<String> {
+    let (label, unit) = match goal.kind {
+        GoalKind::DailyFocus => ("Daily focus", "days"),
+        GoalKind::DailyPomodoros => ("Daily pomodoros", "days"),
+        GoalKind::WeeklyFocus => ("Weekly focus", "weeks"),
+    };
+    let amount = |value: i64| match goal.kind {
+        GoalKind::DailyPomodoros => format!("{} 🍅", value),
+        _ => format_duration(Duration::seconds(value)),
+    };
+
+    let status = if !goal.applies_today {
+        " (rest day)"
+    } else if goal.is_met() {
+        " ✅"
+    } else {
+        ""
+    };
+    vec![
+        format!(
+            "🎯 {}: {} {} / {}{}",
+            label,
+            progress_bar(goal.fraction(), 20),
+            amount(goal.achieved),
+            amount(goal.target),
+            status
+        ),
+        format!(
+            "   🔥 Streak: {} {} (best: {})",
+            goal.current_streak, unit, goal.longest_streak
+        ),
+    ]
+}
+
+/// Time zone, day start and week start, one setting per line.
+pub fn calendar_lines(config: &CalendarConfig) -> Vec<String> {
+    vec![
+        format!(
+            "Time zone: {}",
+            config.timezone.as_deref().unwrap_or("system")
+        ),
+        format!("Day starts at: {}", config.day_starts_at.format("%H:%M")),
+        format!("Week starts on: {}", config.week_starts_on),
+    ]
+}
+
+/// Compact "4h 05m" form for table cells.
+pub fn format_short_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Change from the previous period, e.g. "+1h 30m (+25%)".
+pub fn format_change(current: Duration, previous: Duration) -> String {
+    if previous.is_zero() {
+        return if current.is_zero() {
+            "=".to_string()
+        } else {
+            "new".to_string()
+        };
+    }
+    let delta = current - previous;
+    if delta.is_zero() {
+        return "=".to_string();
+    }
+    let sign = if delta < Duration::zero() { "-" } else { "+" };
+    format!(
+        "{}{} ({}{:.0}%)",
+        sign,
+        format_short_duration(delta.abs()),
+        sign,
+        delta.num_seconds().abs() as f64 / previous.num_seconds() as f64 * 100.0
+    )
+}
+
+/// Title of a week or month view, e.g. "Week of 2026-03-02" or "March 2026".
+pub fn period_title(summary: &PeriodSummary) -> String {
+    let start = summary.period.start;
+    match summary.period.kind {
+        PeriodKind::Day => start.format("%A %Y-%m-%d").to_string(),
+        PeriodKind::Week => format!("Week of {}", start),
+        PeriodKind::Month => start.format("%B %Y").to_string(),
+    }
+}
+
+/// A table with one row per project (worked on in either period), hours
+/// per day, the period total and the change from the previous period.
+pub fn period_table(current: &PeriodSummary, previous: &PeriodSummary) -> Vec<String> {
+    let mut names: Vec<&str> = current
+        .projects
+        .iter()
+        .chain(&previous.projects)
+        .map(|p| p.name.as_str())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let width = names
+        .iter()
+        .map(|n| n.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(7, 20);
+    let days: Vec<_> = current.period.days().collect();
+    let day_header = |day: &chrono::NaiveDate| match current.period.kind {
+        PeriodKind::Month => day.day().to_string(),
+        _ => day.format("%a").to_string(),
+    };
+    let cell = |duration: Duration| {
+        if duration.is_zero() {
+            "·".to_string()
+        } else {
+            format!("{:.1}", duration.num_minutes() as f64 / 60.0)
+        }
+    };
+    let row = |name: &str, per_day: &[Duration], total: Duration, change: String| {
+        let cells: String = per_day.iter().map(|d| format!("{:>5}", cell(*d))).collect();
+        format!(
+            "{:<width$}{}  {:>9}  {}",
+            truncate(name, width),
+            cells,
+            format_short_duration(total),
+            change
+        )
+    };
+
+    let mut lines = vec![format!(
+        "{:<width$}{}  {:>9}  vs previous",
+        "Project",
+        days.iter()
+            .map(|d| format!("{:>5}", day_header(d)))
+            .collect::<String>(),
+        "Total"
+    )];
+    let empty = vec![Duration::zero(); days.len()];
+    for name in names {
+        let per_day = current
+            .projects
+            .iter()
+            .find(|p| p.name == name)
+            .map_or(&empty, |p| &p.per_day);
+        let total = current.project_total(name);
+        lines.push(row(
+            name,
+            per_day,
+            total,
+            format_change(total, previous.project_total(name)),
+        ));
+    }
+    lines.push(row(
+        "Total",
+        &current.daily_totals(),
+        current.total,
+        format_change(current.total, previous.total),
+    ));
+    lines
+}
+
+/// `text` cut to `width` characters, ending in '…' if it was longer.
+pub(crate) fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(width.saturating_sub(1)).collect();
+    short.push('…');
+    short
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or("-".to_string(), |rate| format!("{:.0}%", rate * 100.0))
+}
+
+/// One block character per hour of the day, scaled to the busiest hour.
+fn sparkline(values: &[Duration]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|value| {
+            if value.is_zero() || max.is_zero() {
+                ' '
+            } else {
+                let level = value.num_seconds() as f64 / max.num_seconds() as f64 * 7.0;
+                LEVELS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Summary lines for the Pomodoro statistics view, followed by the
+/// completion rate of each day that had Pomodoros.
+pub fn stats_lines(stats: &PomodoroStats) -> Vec<String> {
+    let average = |duration: Option<Duration>| duration.map_or("-".to_string(), format_duration);
+    let mut lines = vec![
+        format!(
+            "🍅 Completed: {} | Abandoned: {} | Completion rate: {}",
+            stats.completed,
+            stats.abandoned,
+            format_rate(stats.completion_rate())
+        ),
+        format!(
+            "⏱️  Average work: {} | Average break: {}",
+            average(stats.average_work),
+            average(stats.average_break)
+        ),
+        format!(
+            "⏸️  Interruptions: {} pauses in {} sessions",
+            stats.interruptions, stats.interrupted_sessions
+        ),
+    ];
+    if let Some(hour) = stats.best_hour() {
+        lines.push(format!(
+            "🌅 Best time for focus: {:02}:00-{:02}:00",
+            hour,
+            (hour + 1) % 24
+        ));
+        lines.push(format!(
+            "   Focus by hour: │{}│",
+            sparkline(&stats.focus_by_hour)
+        ));
+        lines.push("                   0     6     12    18".to_string());
+    }
+
+    let days: Vec<_> = stats
+        .days
+        .iter()
+        .filter(|(_, day)| day.completed + day.abandoned > 0)
+        .collect();
+    if !days.is_empty() {
+        lines.push(String::new());
+        lines.push("Completion by day:".to_string());
+    }
+    for (date, day) in days {
+        let started = day.completed + day.abandoned;
+        lines.push(format!(
+            "  {}  {} {}/{}",
+            date.format("%a %m-%d"),
+            progress_bar(day.completion_rate().unwrap_or(0.0), 10),
+            day.completed,
+            started
+        ));
+    }
+    lines
+}
@@ -0,0 +1,336 @@
+#[cfg(unix)]
+use crate::daemon::shutdown_signal;
+use axum::extract::{FromRef, Path, Query, Request, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use netupi_core::period::{PeriodKind, PeriodSummary};
+use netupi_core::{
+    NetupiCore, PersistenceError, ProjectSummary, StartRequest, TimerEvent, TimerStatus,
+    WorkSession,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::future::IntoFuture;
+use tokio::sync::watch;
+use tokio_stream::wrappers::{UnboundedReceiverStream, WatchStream};
+use tokio_stream::{Stream, StreamExt};
+
+// Endpoints, all JSON:
+//
+//   GET  /api/status                      TimerStatus
+//   POST /api/timer/start                 body: StartRequest, returns TimerStatus
+//   POST /api/timer/stop                  TimerStatus before stopping, or null
+//   POST /api/timer/pause                 true if a running timer was paused
+//   POST /api/timer/resume                true if a paused timer was resumed
+//   GET  /api/sessions?project=           WorkSession list
+//   GET  /api/projects                    ProjectSummary list, all time
+//   GET  /api/report?days=&project=       ProjectSummary list for the last days
+//   GET  /api/periods/{day|week|month}?offset=
+//                                         PeriodSummary
+//   GET  /api/events                      server-sent TimerEvents
+//
+// Requests must be addressed to localhost, and requests from a browser must
+// come from a local page or an extension, so other websites can't drive the
+// timer or read the log (see `check_origin`).
+
+/// Serve the HTTP API on 127.0.0.1:`port` until Ctrl+C. On Unix the daemon
+/// socket is served too, so CLI timer commands control the same timer.
+pub async fn run(core: NetupiCore, port: u16) -> Result<(), PersistenceError> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+
+    println!("🌐 HTTP API listening on http://127.0.0.1:{}/api", port);
+    println!("Press Ctrl+C to stop the server.");
+    let state = AppState {
+        core: core.clone(),
+        shutdown: shutdown_rx.clone(),
+    };
+    let http = axum::serve(listener, router(state))
+        .with_graceful_shutdown(shutdown_requested(shutdown_rx.clone()))
+        .into_future();
+
+    #[cfg(unix)]
+    {
+        let path = netupi_core::PersistenceManager::new()?.socket_file();
+        let socket = netupi_core::ipc::serve(core.clone(), &path, shutdown_requested(shutdown_rx));
+        tokio::try_join!(async { Ok(http.await?) }, socket)?;
+    }
+    #[cfg(not(unix))]
+    http.await?;
+
+    core.stop().await?;
+    println!("\n👋 Server stopped. The running session, if any, was saved.");
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+async fn shutdown_requested(mut shutdown: watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|requested| *requested).await;
+}
+
+#[derive(Clone)]
+struct AppState {
+    core: NetupiCore,
+    /// Ends open event streams, which would otherwise hold up shutdown.
+    shutdown: watch::Receiver<bool>,
+}
+
+impl FromRef<AppState> for NetupiCore {
+    fn from_ref(state: &AppState) -> Self {
+        state.core.clone()
+    }
+}
+
+impl FromRef<AppState> for watch::Receiver<bool> {
+    fn from_ref(state: &AppState) -> Self {
+        state.shutdown.clone()
+    }
+}
+
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/api/status", get(status))
+        .route("/api/timer/start", post(start))
+        .route("/api/timer/stop", post(stop))
+        .route("/api/timer/pause", post(pause))
+        .route("/api/timer/resume", post(resume))
+        .route("/api/sessions", get(sessions))
+        .route("/api/projects", get(projects))
+        .route("/api/report", get(report))
+        .route("/api/periods/{kind}", get(period))
+        .route("/api/events", get(events))
+        .layer(middleware::from_fn(check_origin))
+        .with_state(state)
+}
+
+/// Refuse requests with a `Host` other than localhost, which is how a DNS
+/// rebinding page would reach the API, and requests that a web page on
+/// another origin sent, which would let any site start or stop the timer.
+async fn check_origin(request: Request, next: Next) -> Response {
+    match origin_error(request.headers()) {
+        Some(error) => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorBody {
+                error: error.to_string(),
+            }),
+        )
+            .into_response(),
+        None => next.run(request).await,
+    }
+}
+
+fn origin_error(headers: &HeaderMap) -> Option<&'static str> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    let host = header(header::HOST).unwrap_or("");
+    if !is_local_host(host) {
+        return Some("requests must be addressed to localhost");
+    }
+    match header(header::ORIGIN) {
+        None => None,
+        Some(origin) if is_allowed_origin(origin) => None,
+        Some(_) => Some("requests from other websites are not allowed"),
+    }
+}
+
+/// `localhost`, `127.0.0.1` or `[::1]`, with or without a port.
+fn is_local_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if !name.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    matches!(name, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Pages served from localhost and browser extensions, which can't be
+/// installed by a website.
+fn is_allowed_origin(origin: &str) -> bool {
+    if origin.starts_with("chrome-extension://") || origin.starts_with("moz-extension://") {
+        return true;
+    }
+    ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| origin.strip_prefix(scheme))
+        .any(is_local_host)
+}
+
+/// A `PersistenceError` as a JSON `{"error": ...}` body: 400 for bad input
+/// such as an unknown preset, 500 for anything else.
+struct ApiError(PersistenceError);
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl From<PersistenceError> for ApiError {
+    fn from(error: PersistenceError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self.0 {
+            PersistenceError::Config(message) => (StatusCode::BAD_REQUEST, message),
+            error => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+        };
+        (status, Json(ErrorBody { error })).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn status(State(core): State<NetupiCore>) -> ApiResult<TimerStatus> {
+    Ok(Json(core.status().await?))
+}
+
+async fn start(
+    State(core): State<NetupiCore>,
+    Json(request): Json<StartRequest>,
+) -> ApiResult<TimerStatus> {
+    core.start(request).await?;
+    Ok(Json(core.status().await?))
+}
+
+async fn stop(State(core): State<NetupiCore>) -> ApiResult<Option<TimerStatus>> {
+    Ok(Json(core.stop().await?))
+}
+
+async fn pause(State(core): State<NetupiCore>) -> Json<bool> {
+    Json(core.timer().pause_timer().await)
+}
+
+async fn resume(State(core): State<NetupiCore>) -> Json<bool> {
+    Json(core.timer().resume_timer().await)
+}
+
+#[derive(Deserialize)]
+struct SessionsQuery {
+    project: Option<String>,
+}
+
+async fn sessions(
+    State(core): State<NetupiCore>,
+    Query(query): Query<SessionsQuery>,
+) -> ApiResult<Vec<WorkSession>> {
+    let sessions = match query.project {
+        Some(project) => core.get_sessions_for_project(&project).await?,
+        None => core.get_sessions().await?,
+    };
+    Ok(Json(sessions))
+}
+
+async fn projects(State(core): State<NetupiCore>) -> ApiResult<Vec<ProjectSummary>> {
+    Ok(Json(core.get_project_summaries().await?))
+}
+
+#[derive(Deserialize)]
+struct ReportQuery {
+    days: Option<u32>,
+    project: Option<String>,
+}
+
+async fn report(
+    State(core): State<NetupiCore>,
+    Query(query): Query<ReportQuery>,
+) -> ApiResult<Vec<ProjectSummary>> {
+    let days = match query.days {
+        Some(days) => days,
+        None => core.timer().get_config().await.report_days,
+    };
+    Ok(Json(
+        core.get_report(Some(days), query.project.as_deref())
+            .await?,
+    ))
+}
+
+#[derive(Deserialize)]
+struct PeriodQuery {
+    #[serde(default)]
+    offset: u32,
+}
+
+async fn period(
+    State(core): State<NetupiCore>,
+    Path(kind): Path<String>,
+    Query(query): Query<PeriodQuery>,
+) -> ApiResult<PeriodSummary> {
+    let kind: PeriodKind = kind.parse().map_err(PersistenceError::Config)?;
+    Ok(Json(core.get_period_summary(kind, query.offset).await?))
+}
+
+async fn events(
+    State(core): State<NetupiCore>,
+    State(shutdown): State<watch::Receiver<bool>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let shutdown = WatchStream::new(shutdown)
+        .filter(|requested| *requested)
+        .map(|_| None);
+    let events = UnboundedReceiverStream::new(core.timer().subscribe_events())
+        .map(Some)
+        .merge(shutdown)
+        .take_while(Option::is_some)
+        .filter_map(|event| event)
+        .map(|event| {
+            let name = event_name(&event);
+            Ok(Event::default()
+                .event(name)
+                .json_data(&event)
+                .unwrap_or_else(|_| Event::default().event(name)))
+        });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn event_name(event: &TimerEvent) -> &'static str {
+    match event {
+        TimerEvent::Started => "started",
+        TimerEvent::Paused => "paused",
+        TimerEvent::Resumed => "resumed",
+        TimerEvent::Stopped => "stopped",
+        TimerEvent::Completed { .. } => "completed",
+        TimerEvent::Tick(_) => "tick",
+        TimerEvent::BudgetThreshold { .. } => "budget_threshold",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(host: &str, origin: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, host.parse().unwrap());
+        if let Some(origin) = origin {
+            headers.insert(header::ORIGIN, origin.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_only_local_requests_are_allowed() {
+        assert!(origin_error(&headers("localhost:7878", None)).is_none());
+        assert!(origin_error(&headers("127.0.0.1:7878", None)).is_none());
+        assert!(origin_error(&headers("[::1]:7878", None)).is_none());
+        assert!(origin_error(&headers("localhost:7878", Some("http://localhost:3000"))).is_none());
+        assert!(origin_error(&headers("localhost:7878", Some("moz-extension://1234"))).is_none());
+
+        // DNS rebinding: the page's own name resolves to 127.0.0.1.
+        assert!(origin_error(&headers("evil.example:7878", None)).is_some());
+        assert!(origin_error(&headers("localhost.evil.example", None)).is_some());
+        // CSRF: a form or fetch from another site.
+        assert!(origin_error(&headers("localhost:7878", Some("https://evil.example"))).is_some());
+        assert!(origin_error(&headers("localhost:7878", Some("null"))).is_some());
+    }
+}
@@ -1,28 +1,75 @@
-use chrono::Duration;
+use crate::chart;
+use crate::display::{
+    apply_display_config, billing_suffix, calendar_lines, config_lines, continued_message,
+    format_budget_status, format_clock, format_money, format_summary_time, goal_lines,
+    length_label, notification_message, period_table, period_title, preset_lines,
+    project_billing_lines, stats_lines,
+};
+use crate::prompt::{PromptHelper, split_args};
+use chrono::{Duration, Weekday};
 use crossterm::style::Stylize;
-use netupi_core::{NetupiCore, PersistenceError, TimerState, TimerType};
-use rustyline::DefaultEditor;
+use netupi_core::billing;
+use netupi_core::budget::{Budget, BudgetPeriod, parse_budget_hours};
+use netupi_core::calendar;
+use netupi_core::duration;
+use netupi_core::goals::GoalKind;
+use netupi_core::hooks::HookRunner;
+use netupi_core::notify::{self, Notification, NotificationSink, Notifier};
+use netupi_core::period::PeriodKind;
+use netupi_core::{NetupiCore, PersistenceError, TimerConfig, TimerState, TimerType};
 use rustyline::error::ReadlineError;
-use std::sync::Arc;
+use rustyline::history::DefaultHistory;
+use rustyline::{Editor, ExternalPrinter};
+use std::sync::{Arc, Mutex};
+
+/// Prints notifications above the prompt as they arrive, without waiting
+/// for the next command, or to stdout when there's no prompt.
+pub struct PrintSink(Option<Mutex<Box<dyn ExternalPrinter + Send>>>);
+
+impl PrintSink {
+    pub fn new(printer: Option<impl ExternalPrinter + Send + 'static>) -> Self {
+        Self(printer.map(|printer| Mutex::new(Box::new(printer) as Box<_>)))
+    }
+
+    pub fn stdout() -> Self {
+        Self(None)
+    }
+}
+
+impl NotificationSink for PrintSink {
+    fn notify(&self, notification: &Notification) {
+        let message = notification_message(notification);
+        match &self.0 {
+            Some(printer) => {
+                if let Ok(mut printer) = printer.lock() {
+                    let _ = printer.print(message);
+                }
+            }
+            None => println!("{}", message),
+        }
+    }
+}
 
 pub struct InteractiveMode {
     core: Arc<NetupiCore>, // Fix: Changed from NetupiCore to Arc<NetupiCore>
-    editor: DefaultEditor,
+    editor: Editor<PromptHelper, DefaultHistory>,
 }
 
 impl InteractiveMode {
     pub async fn new() -> Result<Self, PersistenceError> {
         let core = Arc::new(NetupiCore::new().await?);
-        let mut editor = DefaultEditor::new().map_err(|e| {
-            PersistenceError::Io(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to create editor: {}", e),
-            ))
+        let mut editor = Editor::new().map_err(|e| {
+            PersistenceError::Io(std::io::Error::other(format!(
+                "Failed to create editor: {}",
+                e
+            )))
         })?;
 
+        editor.set_helper(Some(PromptHelper::default()));
+
         // Load history
         #[cfg(feature = "with-file-history")]
-        if let Err(_) = editor.load_history(".netupi_history") {
+        if editor.load_history(".netupi_history").is_err() {
             // History doesn't exist yet, that's ok
         }
 
@@ -31,10 +78,17 @@ impl InteractiveMode {
 
     pub async fn run(&mut self) -> Result<(), PersistenceError> {
         self.print_welcome().await;
+        let mut sinks = notify::default_sinks();
+        sinks.push(Arc::new(PrintSink::new(
+            self.editor.create_external_printer().ok(),
+        )));
+        let notifier = Notifier::spawn(&self.core, sinks);
+        let hooks = HookRunner::spawn(&self.core);
 
         loop {
+            self.update_completions().await;
             let prompt = "netupi> ";
-            let readline = self.editor.readline(&prompt);
+            let readline = self.editor.readline(prompt);
 
             match readline {
                 Ok(line) => {
@@ -44,7 +98,7 @@ impl InteractiveMode {
                     }
 
                     // Add to history
-                    if let Err(_) = self.editor.add_history_entry(line) {
+                    if self.editor.add_history_entry(line).is_err() {
                         // History error, continue anyway
                     }
 
@@ -70,13 +124,25 @@ impl InteractiveMode {
 
         // Save history before exiting
         #[cfg(feature = "with-file-history")]
-        if let Err(_) = self.editor.save_history(".netupi_history") {
+        if self.editor.save_history(".netupi_history").is_err() {
             // History save failed, but don't error out
         }
 
+        hooks.finish().await;
+        notifier.finish().await;
         Ok(())
     }
 
+    /// Give the completer the current project and preset names.
+    async fn update_completions(&mut self) {
+        let projects = self.core.get_projects().await.unwrap_or_default();
+        let presets = self.core.timer().get_config().await.presets;
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.projects = projects.into_iter().map(|(name, _)| name).collect();
+            helper.presets = presets.into_keys().collect();
+        }
+    }
+
     async fn stop_current_timer_if_running(&mut self) -> Result<bool, PersistenceError> {
         let state = self.core.timer().get_current_state().await;
 
@@ -115,19 +181,28 @@ impl InteractiveMode {
     }
 
     async fn handle_command(&mut self, line: &str) -> Result<(), PersistenceError> {
-        let parts: Vec<&str> = line.trim().split_whitespace().collect();
+        let args = match split_args(line) {
+            Ok(args) => args,
+            Err(e) => {
+                println!("❌ {}", e);
+                return Ok(());
+            }
+        };
+        let parts: Vec<&str> = args.iter().map(String::as_str).collect();
         if parts.is_empty() {
             return Ok(());
         }
 
         match parts[0].to_lowercase().as_str() {
             "work" => self.cmd_work(&parts[1..]).await,
-            "pomodoro" | "pomo" => self.cmd_pomodoro().await,
+            "pomodoro" | "pomo" => self.cmd_pomodoro(&parts[1..]).await,
             "break" => self.cmd_break().await,
+            "timer" => self.cmd_timer(&parts[1..]).await,
+            "continue" => self.cmd_continue(&parts[1..]).await,
             "stop" => self.cmd_stop().await,
             "status" | "s" => self.cmd_status().await,
             "help" | "h" => {
-                self.cmd_help();
+                self.cmd_help().await;
                 Ok(())
             }
             "clear" | "cls" => {
@@ -138,8 +213,20 @@ impl InteractiveMode {
             "resume" => self.cmd_resume().await,
             "projects" => self.cmd_projects().await,
             "today" => self.cmd_today().await,
+            "week" => self.cmd_period(PeriodKind::Week, &parts[1..]).await,
+            "month" => self.cmd_period(PeriodKind::Month, &parts[1..]).await,
+            "chart" => self.cmd_chart(&parts[1..]).await,
+            "stats" => self.cmd_stats(&parts[1..]).await,
             "project" => self.cmd_project(&parts[1..]).await,
             "delete-project" => self.cmd_delete_project(&parts[1..]).await,
+            "billable" => self.cmd_billable(&parts[1..]).await,
+            "rate" => self.cmd_rate(&parts[1..]).await,
+            "budget" => self.cmd_budget(&parts[1..]).await,
+            "goals" => self.cmd_goals().await,
+            "goal" => self.cmd_goal(&parts[1..]).await,
+            "calendar" => self.cmd_calendar(&parts[1..]).await,
+            "config" => self.cmd_config(&parts[1..]).await,
+            "preset" | "presets" => self.cmd_preset(&parts[1..]).await,
 
             "exit" | "quit" | "q" => std::process::exit(0),
             _ => {
@@ -177,25 +264,54 @@ impl InteractiveMode {
             println!("{}", format!("📝 Description: {}", desc).blue());
         }
 
-        self.core.start_work_session(project, description).await?;
+        self.core
+            .start_work_session(project.clone(), description)
+            .await?;
 
         println!(
             "{}",
             "⏰ Work timer started! Use 'stop' to finish or 'status' to check progress.".yellow()
         );
+        if let Some(status) = self.core.get_budget_status(&project).await? {
+            println!("📊 Budget: {}", format_budget_status(&status));
+        }
 
         Ok(())
     }
 
-    async fn cmd_pomodoro(&mut self) -> Result<(), PersistenceError> {
+    async fn cmd_pomodoro(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
         // Stop current timer if running
         let _had_running_timer = self.stop_current_timer_if_running().await?;
 
-        println!("{}", "🍅 Starting 25-minute Pomodoro session...".green());
+        let (preset, args) = match args {
+            ["--preset", name, rest @ ..] => (Some(name.to_string()), rest),
+            _ => (None, args),
+        };
+        let project = args.first().map(|p| p.to_string());
+        let description = if args.len() > 1 {
+            Some(args[1..].join(" "))
+        } else {
+            None
+        };
+
         self.core
-            .timer()
-            .start_timer(TimerType::PomodoroWork)
+            .start_pomodoro(project, description, preset)
             .await?;
+        let length = self.core.timer().get_current_state().await.target_duration;
+        println!(
+            "{}",
+            format!(
+                "🍅 Starting {} Pomodoro session...",
+                length.map(length_label).unwrap_or_default()
+            )
+            .green()
+        );
+        if let Some(preset) = self.core.timer().cycle_preset().await {
+            println!("{}", format!("🎛️  Preset: {}", preset).blue());
+        }
+        if let Some(project) = self.core.timer().cycle_project().await {
+            println!("{}", format!("🏗️  Project: {}", project).blue());
+        }
 
         println!(
             "{}",
@@ -209,11 +325,22 @@ impl InteractiveMode {
     async fn cmd_break(&mut self) -> Result<(), PersistenceError> {
         let _had_running_timer = self.stop_current_timer_if_running().await?;
 
-        println!("{}", "☕ Starting short break...".green());
         self.core
             .timer()
             .start_timer(TimerType::PomodoroShortBreak)
             .await?;
+        let length = self.core.timer().get_current_state().await.target_duration;
+        println!(
+            "{}",
+            format!(
+                "☕ Starting {} break...",
+                length.map(length_label).unwrap_or_default()
+            )
+            .green()
+        );
+        if let Some(project) = self.core.timer().cycle_project().await {
+            println!("{}", format!("🏗️  Project: {}", project).blue());
+        }
 
         println!(
             "{}",
@@ -223,6 +350,57 @@ impl InteractiveMode {
         Ok(())
     }
 
+    async fn cmd_timer(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let Some(duration) = args.first() else {
+            println!("❌ Usage: timer <duration> [label]");
+            println!("   Example: timer 45m, timer 1h30m laundry");
+            return Ok(());
+        };
+        let duration = match duration::parse_duration(duration) {
+            Ok(duration) => duration,
+            Err(e) => {
+                println!("❌ {}", e);
+                return Ok(());
+            }
+        };
+        let label = (args.len() > 1).then(|| args[1..].join(" "));
+
+        let _had_running_timer = self.stop_current_timer_if_running().await?;
+
+        let message = match &label {
+            Some(label) => format!("⏳ Starting {} timer: {}", format_clock(duration), label),
+            None => format!("⏳ Starting {} timer", format_clock(duration)),
+        };
+        println!("{}", message.green());
+        self.core.start_countdown(duration, label).await?;
+
+        println!(
+            "{}",
+            "⏰ Timer started! You'll be told when it's done; use 'stop' to cancel it.".yellow()
+        );
+
+        Ok(())
+    }
+
+    async fn cmd_continue(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let n = match args.first().map(|n| n.parse::<usize>()) {
+            None => 1,
+            Some(Ok(n)) if n > 0 => n,
+            Some(_) => {
+                println!("❌ Usage: continue [n] (1 = the last session)");
+                return Ok(());
+            }
+        };
+        let extended = self.core.continue_session(n).await?;
+        let status = self.core.status().await?;
+        println!("{}", continued_message(extended, &status).green());
+        println!(
+            "{}",
+            "⏰ Timer started! Use 'stop' to finish or 'status' to check progress.".yellow()
+        );
+        Ok(())
+    }
+
     async fn cmd_stop(&mut self) -> Result<(), PersistenceError> {
         self.stop_current_timer_if_running().await?;
         Ok(())
@@ -246,7 +424,7 @@ impl InteractiveMode {
             TimerType::PomodoroShortBreak => "☕",
             TimerType::PomodoroLongBreak => "🛌",
             TimerType::Stopwatch => "⚡",
-            _ => "⏰",
+            TimerType::CustomTimer => "⏳",
         };
 
         let timer_type_name = match state.timer_type {
@@ -254,7 +432,7 @@ impl InteractiveMode {
             TimerType::PomodoroShortBreak => "Short Break",
             TimerType::PomodoroLongBreak => "Long Break",
             TimerType::Stopwatch => "Work Session",
-            _ => "Timer",
+            TimerType::CustomTimer => "Timer",
         };
 
         println!(
@@ -270,25 +448,67 @@ impl InteractiveMode {
         } else {
             println!("⏱️  Elapsed time: {:02}:{:02}", remaining_minutes, seconds);
         }
+        if let Some(remaining) = state.remaining() {
+            println!("⏳ Remaining: {}", format_clock(remaining));
+        }
+
+        if let Some(project) = self
+            .core
+            .timer()
+            .current_session()
+            .await
+            .and_then(|s| s.tags.first().cloned())
+        {
+            println!("📁 Project: {}", project);
+            if let Some(status) = self.core.get_budget_status(&project).await? {
+                println!("📊 Budget: {}", format_budget_status(&status));
+            }
+        }
 
         Ok(())
     }
 
-    fn cmd_help(&self) {
+    async fn cmd_help(&self) {
+        let config = self.core.timer().get_config().await;
         println!("🌻 Netupi23 Commands:");
         println!("  work <project> [description] - Start work timer for a project");
-        println!("  pomodoro (or pomo)          - Start 25-minute Pomodoro session");
-        println!("  break                       - Start short break timer");
+        println!(
+            "  pomodoro [project] [desc]   - Start {} Pomodoro session",
+            length_label(config.pomodoro_work_duration)
+        );
+        println!("                                (or pomo; keeps the cycle's project)");
+        println!("  pomodoro --preset <name> ...  - Use a preset's lengths for this cycle");
+        println!(
+            "  break                       - Start {} break timer",
+            length_label(config.pomodoro_short_break_duration)
+        );
+        println!("  timer <duration> [label]    - Start a countdown, e.g. timer 45m or 1h30m");
+        println!("  continue [n]                - Start again on the last (or n-th last) session");
         println!("  stop                        - Stop current timer and save session");
         println!("  status (or s)               - Show current timer status");
         println!("  pause                       - Pause current timer");
         println!("  resume                      - Resume paused timer");
         println!("  projects                    - List all projects");
         println!("  today                       - Show today's work summary");
+        println!("  week [offset]               - Show a week per project and day (1 = last week)");
+        println!("  month [offset]              - Show a month per project and day");
+        println!("  chart [week|month|heatmap] [offset] - Draw hours per day, or the past year");
+        println!("  stats [days]                - Show Pomodoro stats (default: last 7 days)");
         println!("  project <name>              - Show details and sessions for a project");
         println!(
             "  delete-project <name>       - Delete all sessions for a project (irreversible!)"
         );
+        println!("  billable <name> <yes|no>    - Mark a project as billable or not");
+        println!("  rate <name> <amount> [cur]  - Set a project's hourly rate (default USD)");
+        println!("  budget <name> <hours|off>   - Set or clear a project's budget [total|weekly]");
+        println!("  goals                       - Show goal progress and streaks");
+        println!("  goal <kind> <value|off>     - Set a goal: daily, weekly (hours), pomodoros");
+        println!("  calendar [setting] [value]  - Show or set timezone, day-start, week-start");
+        println!("  config [get|set|reset] ...  - List or change settings, e.g. timer.work");
+        println!("  preset [name]               - List presets or make one the default");
+        println!(
+            "  preset save|delete <name>   - Save the current lengths as a preset, or delete one"
+        );
         println!("  clear (or cls)              - Clear screen");
         println!("  help (or h)                 - Show this help");
         println!("  exit/quit (or q)            - Exit interactive mode");
@@ -302,12 +522,20 @@ impl InteractiveMode {
     }
 
     async fn cmd_pause(&mut self) -> Result<(), PersistenceError> {
-        println!("⏸️ Pause functionality coming soon!");
+        if self.core.timer().pause_timer().await {
+            println!("⏸️ Timer paused. Use 'resume' to continue.");
+        } else {
+            println!("ℹ️ No running timer to pause.");
+        }
         Ok(())
     }
 
     async fn cmd_resume(&mut self) -> Result<(), PersistenceError> {
-        println!("▶️ Resume functionality coming soon!");
+        if self.core.timer().resume_timer().await {
+            println!("▶️ Timer resumed.");
+        } else {
+            println!("ℹ️ No paused timer to resume.");
+        }
         Ok(())
     }
 
@@ -315,20 +543,18 @@ impl InteractiveMode {
         println!("📂 Your Projects:");
         println!("==================");
 
-        match self.core.get_projects().await {
+        match self.core.get_project_summaries().await {
             Ok(projects) => {
                 if projects.is_empty() {
                     println!("No projects yet. Start working on one with 'work <project>'!");
                 } else {
-                    for (project, duration) in projects {
-                        let total_minutes = duration.num_minutes();
-                        let hours = total_minutes / 60;
-                        let minutes = total_minutes % 60;
-                        if hours > 0 {
-                            println!("{}: {} hours {} minutes", project, hours, minutes);
-                        } else {
-                            println!("{}: {} minutes", project, minutes);
-                        }
+                    for summary in projects {
+                        println!(
+                            "{}: {}{}",
+                            summary.name,
+                            format_summary_time(&summary),
+                            billing_suffix(&summary)
+                        );
                     }
                 }
             }
@@ -345,24 +571,34 @@ impl InteractiveMode {
         println!("📅 Today's Work Summary:");
         println!("======================");
 
-        match self.core.get_today_summary().await {
-            Ok(summary) => {
-                let mut today_projects: Vec<(String, chrono::Duration)> =
-                    summary.into_iter().collect();
-                today_projects.sort_by(|a, b| a.0.cmp(&b.0));
+        let daily_goals: Vec<_> = self
+            .core
+            .get_goal_progress()
+            .await?
+            .into_iter()
+            .filter(|g| g.kind != GoalKind::WeeklyFocus)
+            .collect();
+        for goal in &daily_goals {
+            for line in goal_lines(goal) {
+                println!("{}", line);
+            }
+        }
+        if !daily_goals.is_empty() {
+            println!();
+        }
 
+        match self.core.get_today_project_summaries().await {
+            Ok(today_projects) => {
                 if today_projects.is_empty() {
                     println!("No work sessions today yet. Get started with 'work <project>'!");
                 } else {
-                    for (project, duration) in today_projects {
-                        let total_minutes = duration.num_minutes();
-                        let hours = total_minutes / 60;
-                        let minutes = total_minutes % 60;
-                        if hours > 0 {
-                            println!("{}: {} hours {} minutes", project, hours, minutes);
-                        } else {
-                            println!("{}: {} minutes", project, minutes);
-                        }
+                    for summary in today_projects {
+                        println!(
+                            "{}: {}{}",
+                            summary.name,
+                            format_summary_time(&summary),
+                            billing_suffix(&summary)
+                        );
                     }
                 }
             }
@@ -375,6 +611,96 @@ impl InteractiveMode {
         Ok(())
     }
 
+    async fn cmd_period(
+        &mut self,
+        kind: PeriodKind,
+        args: &[&str],
+    ) -> Result<(), PersistenceError> {
+        let offset = match args.first().map(|a| a.parse::<u32>()) {
+            None => 0,
+            Some(Ok(offset)) => offset,
+            Some(Err(_)) => {
+                println!("❌ Usage: {} [offset]", kind);
+                println!("   Example: {} 1 (the previous {})", kind, kind);
+                return Ok(());
+            }
+        };
+
+        let current = self.core.get_period_summary(kind, offset).await?;
+        let previous = self.core.get_summary_for(current.period.previous()).await?;
+
+        println!("📅 {}", period_title(&current));
+        if current.projects.is_empty() && previous.projects.is_empty() {
+            println!("No work sessions in this {} or the previous one.", kind);
+        } else {
+            for line in period_table(&current, &previous) {
+                println!("{}", line);
+            }
+        }
+        println!();
+        Ok(())
+    }
+
+    async fn cmd_chart(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let view = args.first().copied().unwrap_or("week");
+        if view == "heatmap" {
+            let calendar = self.core.calendar().await?;
+            let today = calendar.today();
+            let start = calendar.week_of(today) - Duration::weeks(52);
+            let totals = self
+                .core
+                .get_daily_totals(start, today + Duration::days(1))
+                .await?;
+            println!("🗓️  Past Year:");
+            for line in chart::heatmap(start, &totals) {
+                println!("{}", line);
+            }
+            println!();
+            return Ok(());
+        }
+
+        let kind = view.parse::<PeriodKind>();
+        let offset = args.get(1).map_or(Ok(0), |a| a.parse::<u32>());
+        let (Ok(kind), Ok(offset)) = (kind, offset) else {
+            println!("❌ Usage: chart [week|month|heatmap] [offset]");
+            println!("   Examples: chart, chart month 1, chart heatmap");
+            return Ok(());
+        };
+
+        let summary = self.core.get_period_summary(kind, offset).await?;
+        println!("📊 {}", period_title(&summary));
+        let width = self.core.timer().get_config().await.display.chart_width;
+        for line in chart::bar_chart(&summary, width) {
+            println!("{}", line);
+        }
+        println!();
+        Ok(())
+    }
+
+    async fn cmd_stats(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let days = match args.first().map(|a| a.parse::<u32>()) {
+            None => self.core.timer().get_config().await.report_days,
+            Some(Ok(days)) => days,
+            Some(Err(_)) => {
+                println!("❌ Usage: stats [days]");
+                println!("   Example: stats 30");
+                return Ok(());
+            }
+        };
+
+        println!("🍅 Pomodoro Stats (last {} days):", days.max(1));
+        let stats = self.core.get_pomodoro_stats(days).await?;
+        if stats.completed + stats.abandoned == 0 && stats.best_hour().is_none() {
+            println!("No Pomodoros or work sessions yet. Start one with 'pomodoro'!");
+        } else {
+            for line in stats_lines(&stats) {
+                println!("{}", line);
+            }
+        }
+        println!();
+        Ok(())
+    }
+
     async fn cmd_project(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
         if args.is_empty() {
             println!("❌ Usage: project <project-name>");
@@ -389,6 +715,9 @@ impl InteractiveMode {
             println!("❌ No sessions found for project '{}'.", project);
             return Ok(());
         }
+        let settings = self.core.get_project_settings(&project).await?;
+        let summary = self.core.get_report(None, Some(&project)).await?;
+        let calendar = self.core.calendar().await?;
 
         // Calculate total duration
         let total_duration: Duration = sessions.iter().map(|s| s.duration).sum();
@@ -403,12 +732,33 @@ impl InteractiveMode {
         } else {
             println!("Total time: {} minutes", total_mins);
         }
+        for summary in &summary {
+            for line in project_billing_lines(summary, &settings) {
+                println!("{}", line);
+            }
+        }
+        if let Some(status) = self.core.get_budget_status(&project).await? {
+            println!("Budget: {}", format_budget_status(&status));
+        }
         println!("\nSessions:");
         for session in sessions {
-            let start_str = session.start_time.format("%Y-%m-%d %H:%M").to_string();
+            let billable_marker = if billing::is_billable(&session, Some(&settings)) {
+                " 💵"
+            } else {
+                ""
+            };
+            let pomodoro_marker = if session.session_type == TimerType::PomodoroWork {
+                " 🍅"
+            } else {
+                ""
+            };
+            let start_str = calendar
+                .local(session.start_time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string();
             let end_str = session
                 .end_time
-                .map(|end| end.format("%Y-%m-%d %H:%M").to_string())
+                .map(|end| calendar.local(end).format("%Y-%m-%d %H:%M").to_string())
                 .unwrap_or("Ongoing".to_string());
             let dur_mins = session.duration.num_minutes();
             let dur_str = if dur_mins >= 60 {
@@ -419,10 +769,12 @@ impl InteractiveMode {
                 format!("{} minutes", dur_mins)
             };
             println!(
-                "- {}: End: {} ({}) | Description: {}",
+                "- {}: End: {} ({}){}{} | Description: {}",
                 start_str,
                 end_str,
                 dur_str,
+                pomodoro_marker,
+                billable_marker,
                 session.description.as_deref().unwrap_or("None")
             );
         }
@@ -462,4 +814,307 @@ impl InteractiveMode {
         println!();
         Ok(())
     }
+
+    async fn cmd_billable(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let billable = match args.get(1).map(|a| a.to_lowercase()) {
+            Some(v) if v == "yes" || v == "y" || v == "on" => true,
+            Some(v) if v == "no" || v == "n" || v == "off" => false,
+            _ => {
+                println!("❌ Usage: billable <project-name> <yes|no>");
+                return Ok(());
+            }
+        };
+
+        let project = args[0].to_string();
+        let mut settings = self.core.get_project_settings(&project).await?;
+        settings.billable = billable;
+        self.core.set_project_settings(&project, settings).await?;
+
+        if billable {
+            println!("💵 '{}' is now billable.", project);
+        } else {
+            println!("🆓 '{}' is no longer billable.", project);
+        }
+        Ok(())
+    }
+
+    async fn cmd_rate(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let rate = match args.get(1).map(|a| billing::parse_rate(a)) {
+            Some(Ok(rate)) => rate,
+            Some(Err(e)) => {
+                println!("❌ {}", e);
+                return Ok(());
+            }
+            None => {
+                println!("❌ Usage: rate <project-name> <amount> [currency]");
+                println!("   Example: rate \"Client Project\" 80 EUR");
+                return Ok(());
+            }
+        };
+
+        let project = args[0].to_string();
+        let mut settings = self.core.get_project_settings(&project).await?;
+        settings.hourly_rate = Some(rate);
+        if let Some(currency) = args.get(2) {
+            settings.currency = Some(currency.to_uppercase());
+        }
+        let currency = billing::currency(Some(&settings));
+        self.core.set_project_settings(&project, settings).await?;
+
+        println!(
+            "💵 Rate for '{}' set to {}/h",
+            project,
+            format_money(rate, &currency)
+        );
+        Ok(())
+    }
+
+    async fn cmd_budget(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        if args.len() < 2 {
+            println!("❌ Usage: budget <project-name> <hours|off> [total|weekly]");
+            println!("   Example: budget \"Client Project\" 10 weekly");
+            return Ok(());
+        }
+
+        let project = args[0].to_string();
+        let mut settings = self.core.get_project_settings(&project).await?;
+
+        if args[1].eq_ignore_ascii_case("off") {
+            settings.budget = None;
+            self.core.set_project_settings(&project, settings).await?;
+            println!("🗑️  Budget removed for '{}'.", project);
+            return Ok(());
+        }
+
+        let limit = match parse_budget_hours(args[1]) {
+            Ok(limit) => limit,
+            Err(e) => {
+                println!("❌ {}", e);
+                return Ok(());
+            }
+        };
+        let period = match args.get(2).map(|p| p.parse::<BudgetPeriod>()) {
+            Some(Ok(period)) => period,
+            Some(Err(e)) => {
+                println!("❌ {}", e);
+                return Ok(());
+            }
+            None => BudgetPeriod::Total,
+        };
+
+        settings.budget = Some(Budget { limit, period });
+        self.core.set_project_settings(&project, settings).await?;
+
+        println!(
+            "📊 Budget for '{}' set to {} hours ({}).",
+            project,
+            args[1].trim(),
+            period
+        );
+        if let Some(status) = self.core.get_budget_status(&project).await? {
+            println!("   {}", format_budget_status(&status));
+        }
+        Ok(())
+    }
+
+    async fn cmd_goals(&mut self) -> Result<(), PersistenceError> {
+        println!("🎯 Your Goals:");
+        println!("==============");
+
+        let goals = self.core.get_goal_progress().await?;
+        if goals.is_empty() {
+            println!("No goals set. Try 'goal daily 6' or 'goal pomodoros 8'.");
+        }
+        for goal in goals {
+            for line in goal_lines(&goal) {
+                println!("{}", line);
+            }
+        }
+        println!();
+        Ok(())
+    }
+
+    async fn cmd_goal(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        if args.len() < 2 {
+            println!("❌ Usage: goal <daily|weekly|pomodoros|weekdays> <value|off>");
+            println!("   Examples: goal daily 6, goal pomodoros 8, goal weekdays on");
+            return Ok(());
+        }
+
+        let value = args[1].to_lowercase();
+        let off = value == "off" || value == "0";
+        let hours = duration::parse_hours(&value)
+            .ok()
+            .filter(|h| *h > Duration::zero());
+        let count = value.parse::<u32>().ok().filter(|c| *c > 0);
+
+        let mut config = self.core.timer().get_config().await;
+        let goals = &mut config.goals;
+        let valid = match args[0].to_lowercase().as_str() {
+            "daily" if hours.is_some() || off => {
+                goals.daily_focus = hours;
+                true
+            }
+            "weekly" if hours.is_some() || off => {
+                goals.weekly_focus = hours;
+                true
+            }
+            "pomodoros" | "pomos" if count.is_some() || off => {
+                goals.daily_pomodoros = count;
+                true
+            }
+            "weekdays" if matches!(value.as_str(), "on" | "yes" | "off" | "no") => {
+                goals.weekdays_only = value == "on" || value == "yes";
+                true
+            }
+            _ => false,
+        };
+
+        if !valid {
+            println!(
+                "❌ Invalid goal '{} {}'. Type 'help' for usage.",
+                args[0], args[1]
+            );
+            return Ok(());
+        }
+
+        self.core.timer().set_config(config).await?;
+        println!("✅ Goal updated.");
+        self.cmd_goals().await
+    }
+
+    async fn cmd_calendar(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let mut config = self.core.timer().get_config().await;
+        if !args.is_empty() {
+            if args.len() < 2 {
+                println!("❌ Usage: calendar <timezone|day-start|week-start> <value>");
+                println!(
+                    "   Examples: calendar timezone Europe/Madrid, calendar day-start 04:00, calendar week-start sunday"
+                );
+                return Ok(());
+            }
+
+            let calendar = &mut config.calendar;
+            let valid = match args[0].to_lowercase().as_str() {
+                "timezone" | "tz" => {
+                    calendar.timezone = (args[1] != "system").then(|| args[1].to_string());
+                    true
+                }
+                "day-start" => match calendar::parse_time_of_day(args[1]) {
+                    Ok(time) => {
+                        calendar.day_starts_at = time;
+                        true
+                    }
+                    Err(_) => false,
+                },
+                "week-start" => match args[1].parse::<Weekday>() {
+                    Ok(day) => {
+                        calendar.week_starts_on = day;
+                        true
+                    }
+                    Err(_) => false,
+                },
+                _ => false,
+            };
+
+            if !valid {
+                println!(
+                    "❌ Invalid calendar setting '{} {}'. Type 'help' for usage.",
+                    args[0], args[1]
+                );
+                return Ok(());
+            }
+            self.core.timer().set_config(config.clone()).await?;
+            println!("✅ Calendar updated.");
+        }
+
+        println!("📅 Calendar:");
+        for line in calendar_lines(&config.calendar) {
+            println!("  {}", line);
+        }
+        println!();
+        Ok(())
+    }
+
+    async fn cmd_config(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        let mut config = self.core.timer().get_config().await;
+        let action = args.first().map(|a| a.to_lowercase());
+        match (action.as_deref(), args.len()) {
+            (None | Some("list"), _) => {
+                println!("⚙️  Settings:");
+                for line in config_lines(&config) {
+                    println!("  {}", line);
+                }
+                println!();
+            }
+            (Some("get"), 2) => match config.get(args[1]) {
+                Ok(value) => println!("{} = {}", args[1], value),
+                Err(e) => println!("❌ {}", e),
+            },
+            (Some("set"), n) if n >= 3 => {
+                let value = args[2..].join(" ");
+                if let Err(e) = config.set(args[1], &value) {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+                self.core.timer().set_config(config.clone()).await?;
+                apply_display_config(&config.display);
+                println!("✅ {} = {}", args[1], config.get(args[1])?);
+            }
+            (Some("reset"), 2) => {
+                if let Err(e) = config.reset(args[1]) {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+                self.core.timer().set_config(config.clone()).await?;
+                apply_display_config(&config.display);
+                println!("✅ {} reset to {}", args[1], config.get(args[1])?);
+            }
+            (Some("reset"), 1) => {
+                let config = TimerConfig::default();
+                self.core.timer().set_config(config.clone()).await?;
+                apply_display_config(&config.display);
+                println!("✅ All settings reset to their defaults.");
+            }
+            _ => {
+                println!("❌ Usage: config [list | get <key> | set <key> <value> | reset [key]]");
+                println!("   Examples: config set timer.work 50m, config reset timer.short_break");
+            }
+        }
+        Ok(())
+    }
+
+    async fn cmd_preset(&mut self, args: &[&str]) -> Result<(), PersistenceError> {
+        match args {
+            [] => {}
+            ["save", name] => {
+                let mut config = self.core.timer().get_config().await;
+                config.save_preset(name)?;
+                self.core.timer().set_config(config).await?;
+                println!("✅ Saved the current timer lengths as {}.", name);
+            }
+            ["delete", name] => {
+                let mut config = self.core.timer().get_config().await;
+                config.remove_preset(name)?;
+                self.core.timer().set_config(config).await?;
+                println!("🗑️  Deleted the {} preset.", name);
+            }
+            [name] => {
+                self.core.use_preset(name).await?;
+                println!("✅ Switched to the {} preset.", name);
+            }
+            _ => {
+                println!("❌ Usage: preset [name | save <name> | delete <name>]");
+                println!("   Examples: preset deep, preset save writing");
+                return Ok(());
+            }
+        }
+
+        println!("🍅 Presets:");
+        for line in preset_lines(&self.core.timer().get_config().await) {
+            println!("  {}", line);
+        }
+        println!();
+        Ok(())
+    }
 }
@@ -1,9 +1,43 @@
-use clap::{Parser, Subcommand};
-use netupi_core::{NetupiCore, PersistenceError, TimerState, TimerType};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+use clap_complete::env::CompleteEnv;
+use completions::{CompletionShell, complete_project};
+use netupi_core::billing;
+use netupi_core::budget::{Budget, BudgetPeriod, parse_budget_hours};
+use netupi_core::calendar::{self, Calendar};
+use netupi_core::duration;
+use netupi_core::hooks::HookRunner;
+use netupi_core::invoice::{InvoiceFormat, InvoiceGrouping, InvoiceOptions};
+use netupi_core::notify::{self, Notifier};
+use netupi_core::period::PeriodKind;
+use netupi_core::rounding::{Rounding, RoundingMode, RoundingScope};
+use netupi_core::when;
+use netupi_core::{
+    NetupiCore, PersistenceError, PersistenceManager, StartRequest, TimerConfig, TimerEvent,
+    TimerType, WorkSession,
+};
 use tokio::time::{Duration as TokioDuration, sleep};
 
+mod chart;
+mod completions;
+#[cfg(unix)]
+mod daemon;
+mod display;
+#[cfg(feature = "http-api")]
+mod http;
 mod interactive;
+mod prompt;
+mod statusbar;
+mod tui;
+use display::{
+    apply_display_config, billing_suffix, budget_alert, calendar_lines, completion_message,
+    config_lines, continued_message, format_budget_status, format_clock, format_duration,
+    format_money, format_rounded, format_summary_time, goal_lines, length_label, period_table,
+    period_title, preset_lines, project_billing_lines, stats_lines, status_lines,
+};
 use interactive::InteractiveMode;
+use statusbar::{StatusFormat, status_line};
 
 #[derive(Parser)]
 #[command(name = "netupi")]
@@ -13,60 +47,353 @@ struct Cli {
     command: Option<Commands>,
 }
 
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+enum ChartView {
+    Bars,
+    Heatmap,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Start interactive mode
     Interactive,
+    /// Open a live full-screen dashboard
+    Tui,
     /// Start a Pomodoro work session (25 minutes)
-    Work,
+    Work {
+        /// Project to track the Pomodoro against
+        #[arg(short, long, add = ArgValueCompleter::new(complete_project))]
+        project: Option<String>,
+        /// Optional description of what you're working on
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Use a preset's lengths for this Pomodoro cycle, e.g. deep
+        #[arg(long)]
+        preset: Option<String>,
+        /// When you started, e.g. "10 minutes ago", 9:30 or "yesterday 5pm"
+        #[arg(long)]
+        at: Option<String>,
+    },
     /// Start a short break (5 minutes)
     Break,
     /// Start a long break (15 minutes)
     LongBreak,
     /// Start a stopwatch (no time limit)
     Stopwatch,
+    /// Start a countdown timer, e.g. `netupi timer 45m` or `netupi timer 1h30m tea`
+    Timer {
+        /// How long to count down: 45m, 1h30m, 90s, 1:30:00...
+        #[arg(value_parser = duration::parse_duration)]
+        duration: chrono::Duration,
+        /// What the timer is for
+        label: Vec<String>,
+    },
     /// Start tracking work time on a specific project
+    #[command(visible_alias = "start")]
     TimeTrack {
         /// Name of the project you're working on
+        #[arg(add = ArgValueCompleter::new(complete_project))]
         project: String,
         /// Optional description of what you're working on
         #[arg(short, long)]
         description: Option<String>,
+        /// Mark this session as billable, regardless of the project setting
+        #[arg(long, conflicts_with = "non_billable")]
+        billable: bool,
+        /// Mark this session as non-billable, regardless of the project setting
+        #[arg(long)]
+        non_billable: bool,
+        /// Hourly rate for this session, overriding the project rate
+        #[arg(long, value_parser = billing::parse_rate)]
+        rate: Option<f64>,
+        /// When you started, e.g. "10 minutes ago", 9:30 or "yesterday 5pm"
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Start a new session like the last one (or the n-th last), e.g. after
+    /// lunch
+    Continue {
+        /// Which session to continue, counting back from the last one
+        #[arg(default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        n: u32,
     },
     /// Stop the current timer and save the session
-    Stop,
-    /// Show work time statistics
-    Log,
+    Stop {
+        /// When you stopped, e.g. 17:30 or "5 minutes ago"
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Pause the timer running in the daemon
+    Pause,
+    /// Resume the timer paused in the daemon
+    Resume,
+    /// Keep timers running in the background, so timer commands return
+    /// immediately
+    #[cfg(unix)]
+    Daemon,
+    /// Serve a local HTTP API for editor plugins and scripts
+    #[cfg(feature = "http-api")]
+    Serve {
+        /// Port to listen on, on 127.0.0.1
+        #[arg(long, default_value_t = 7878)]
+        port: u16,
+    },
+    /// List your sessions, newest last
+    Log {
+        /// Earliest time to list, e.g. 2w, yesterday or "last monday"
+        /// (default: the last reports.days days)
+        #[arg(short, long)]
+        since: Option<String>,
+    },
     /// List all projects you've worked on
     Projects,
     /// Show today's work summary
     Today,
+    /// Show this week's time per project and day, compared with last week
+    Week {
+        /// How many weeks to look back (1 = last week)
+        #[arg(short, long, default_value = "0")]
+        offset: u32,
+    },
+    /// Show this month's time per project and day, compared with last month
+    Month {
+        /// How many months to look back (1 = last month)
+        #[arg(short, long, default_value = "0")]
+        offset: u32,
+    },
     /// Show details for a specific project
     Project {
         /// Project name
+        #[arg(add = ArgValueCompleter::new(complete_project))]
         project: String,
     },
     /// Delete all sessions for a specific project
     DeleteProject {
         /// Project name
+        #[arg(add = ArgValueCompleter::new(complete_project))]
+        project: String,
+    },
+    /// Configure billing for a project
+    SetProject {
+        /// Project name
+        #[arg(add = ArgValueCompleter::new(complete_project))]
         project: String,
+        /// Whether time on this project is billable (true/false)
+        #[arg(long)]
+        billable: Option<bool>,
+        /// Hourly rate charged for this project
+        #[arg(long, value_parser = billing::parse_rate)]
+        rate: Option<f64>,
+        /// Currency code for the hourly rate (e.g. EUR)
+        #[arg(long)]
+        currency: Option<String>,
+        /// Round this project's reported time to this many minutes (0 disables)
+        #[arg(long)]
+        round: Option<u32>,
+        /// Rounding direction: up, down or nearest (default: up)
+        #[arg(long, requires = "round")]
+        rounding: Option<RoundingMode>,
+        /// Round each session or the project's total (default: total)
+        #[arg(long, requires = "round")]
+        round_per: Option<RoundingScope>,
+        /// Time budget for the project, in hours
+        #[arg(long, conflicts_with = "clear_budget", value_parser = parse_budget_hours)]
+        budget: Option<chrono::Duration>,
+        /// Whether the budget covers all time or resets weekly (default: total)
+        #[arg(long, requires = "budget")]
+        budget_period: Option<BudgetPeriod>,
+        /// Remove the project's time budget
+        #[arg(long)]
+        clear_budget: bool,
     },
     /// Generate work time reports
     Report {
         /// Filter by specific project
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCompleter::new(complete_project))]
         project: Option<String>,
-        /// Number of days to include in report
-        #[arg(short, long, default_value = "7")]
-        days: u32,
+        /// Number of days to include in report (default: reports.days)
+        #[arg(short, long, conflicts_with_all = ["from", "to"])]
+        days: Option<u32>,
+        /// First day to include, e.g. "last monday", 2w or 2026-10-01
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include, e.g. yesterday
+        #[arg(long)]
+        to: Option<String>,
     },
     /// Show current timer status
-    Status,
+    Status {
+        /// One line for a shell prompt or status bar, e.g. "🍅 12:34 api"
+        #[arg(long)]
+        short: bool,
+        /// One line in a status bar's format
+        #[arg(long, value_enum)]
+        format: Option<StatusFormat>,
+        /// Template for --short and --format template, e.g. "{icon} {clock}"
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Show progress towards your daily/weekly goals, or change them
+    Goals {
+        /// Daily focused-work goal, in hours (0 removes it)
+        #[arg(long, value_parser = duration::parse_hours)]
+        daily_hours: Option<chrono::Duration>,
+        /// Daily goal of completed Pomodoros (0 removes it)
+        #[arg(long)]
+        daily_pomodoros: Option<u32>,
+        /// Weekly focused-work goal, in hours (0 removes it)
+        #[arg(long, value_parser = duration::parse_hours)]
+        weekly_hours: Option<chrono::Duration>,
+        /// Only apply daily goals on weekdays (true/false)
+        #[arg(long)]
+        weekdays_only: Option<bool>,
+    },
+    /// Show Pomodoro statistics: completion, averages, interruptions and best focus hours
+    Stats {
+        /// Number of days to include, including today (default: reports.days)
+        #[arg(short, long)]
+        days: Option<u32>,
+    },
+    /// Draw a bar chart of hours per day and project, and a heatmap of the past year
+    Chart {
+        /// Which chart to draw (both by default)
+        view: Option<ChartView>,
+        /// Period for the bar chart: week or month
+        #[arg(short, long, default_value = "week")]
+        period: PeriodKind,
+        /// How many periods to look back (1 = last week/month)
+        #[arg(short, long, default_value = "0")]
+        offset: u32,
+    },
+    /// Show or change settings such as timer lengths
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    /// List Pomodoro presets, or make one the default timer lengths
+    Preset {
+        /// Preset to switch to
+        name: Option<String>,
+        /// Save the current timer lengths as a preset with this name
+        #[arg(long, conflicts_with_all = ["name", "delete"])]
+        save: Option<String>,
+        /// Delete the preset with this name
+        #[arg(long, conflicts_with = "name")]
+        delete: Option<String>,
+    },
+    /// Show or change how time is split into days and weeks
+    Calendar {
+        /// IANA time zone, e.g. Europe/Madrid ("system" uses the system time zone)
+        #[arg(long)]
+        timezone: Option<String>,
+        /// Local time at which a new day begins (HH:MM), e.g. 04:00
+        #[arg(long, value_parser = calendar::parse_time_of_day)]
+        day_starts_at: Option<chrono::NaiveTime>,
+        /// First day of the week, e.g. monday or sunday
+        #[arg(long)]
+        week_start: Option<chrono::Weekday>,
+    },
+    /// Generate an invoice from billable sessions and mark them as invoiced
+    Invoice {
+        /// Project to invoice
+        #[arg(short, long, add = ArgValueCompleter::new(complete_project))]
+        project: String,
+        /// First day to include, e.g. 2026-10-01 or "last week"
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include, e.g. 2026-10-31 or yesterday
+        #[arg(long)]
+        to: Option<String>,
+        /// Group line items by day or task
+        #[arg(long, default_value = "day")]
+        group_by: InvoiceGrouping,
+        /// Output format: markdown, html or text
+        #[arg(short, long, default_value = "markdown")]
+        format: InvoiceFormat,
+        /// Round to this many minutes instead of the configured rule (0 disables rounding)
+        #[arg(long)]
+        round: Option<u32>,
+        /// Rounding direction: up, down or nearest (default: up)
+        #[arg(long, requires = "round")]
+        rounding: Option<RoundingMode>,
+        /// Round each session or each line item's total (default: total)
+        #[arg(long, requires = "round")]
+        round_per: Option<RoundingScope>,
+        /// Invoice number (defaults to the next sequential number)
+        #[arg(long)]
+        number: Option<String>,
+        /// Write the invoice to a file instead of printing it
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Preview the invoice without marking sessions as invoiced
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print a script that sets up tab completion for a shell
+    Completions {
+        /// Shell to complete in
+        shell: CompletionShell,
+    },
+    /// Print the man page
+    Man {
+        /// Write netupi.1 and a page per subcommand to this directory instead
+        #[arg(long)]
+        out_dir: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// List every setting with its value (the default)
+    List,
+    /// Show the value of a setting, e.g. timer.work
+    Get { key: String },
+    /// Change a setting, e.g. `netupi config set timer.work 50m`
+    Set { key: String, value: String },
+    /// Restore one setting, or all of them, to the default
+    Reset { key: Option<String> },
+}
+
+/// The command-line parser, with timer help texts showing the configured
+/// lengths.
+fn cli_command(config: &TimerConfig) -> clap::Command {
+    Cli::command()
+        .mut_subcommand("work", |c| {
+            c.about(format!(
+                "Start a Pomodoro work session ({})",
+                format_duration(config.pomodoro_work_duration)
+            ))
+        })
+        .mut_subcommand("break", |c| {
+            c.about(format!(
+                "Start a short break ({})",
+                format_duration(config.pomodoro_short_break_duration)
+            ))
+        })
+        .mut_subcommand("long-break", |c| {
+            c.about(format!(
+                "Start a long break ({})",
+                format_duration(config.pomodoro_long_break_duration)
+            ))
+        })
 }
 
 #[tokio::main]
 async fn main() -> Result<(), PersistenceError> {
-    let cli = Cli::parse();
+    // Answer the completion scripts' requests, then exit.
+    CompleteEnv::with_factory(|| cli_command(&TimerConfig::default()))
+        .var(completions::COMPLETE_VAR)
+        .complete();
+
+    // A broken config file is reported once the core loads it; help and
+    // argument parsing fall back to the defaults.
+    let config = match PersistenceManager::new() {
+        Ok(persistence) => persistence.load_config().await.unwrap_or_default(),
+        Err(_) => TimerConfig::default(),
+    };
+    apply_display_config(&config.display);
+    let cli =
+        Cli::from_arg_matches(&cli_command(&config).get_matches()).unwrap_or_else(|e| e.exit());
 
     // If no command provided, start interactive mode
     match cli.command {
@@ -78,6 +405,25 @@ async fn main() -> Result<(), PersistenceError> {
             let mut interactive = InteractiveMode::new().await?;
             interactive.run().await?;
         }
+        Some(Commands::Tui) => tui::run(NetupiCore::new().await?).await?,
+        // Status bars run this every second: skip the banner and the core,
+        // and only read the active timer file.
+        Some(Commands::Status {
+            short,
+            format,
+            template,
+        }) if short || format.is_some() => {
+            let active = PersistenceManager::new()?.load_active_timer().await?;
+            let format = format.unwrap_or(StatusFormat::Template);
+            println!(
+                "{}",
+                status_line(active.as_ref(), format, template.as_deref(), &config)
+            );
+        }
+        Some(Commands::Completions { shell }) => completions::print_completions(shell)?,
+        Some(Commands::Man { out_dir }) => {
+            completions::man_pages(cli_command(&config), out_dir.as_deref())?
+        }
         Some(command) => {
             // Handle single commands
             handle_single_command(command).await?;
@@ -91,11 +437,36 @@ async fn handle_single_command(command: Commands) -> Result<(), PersistenceError
     println!("🌻 Netupi23 - Minimalist Timer");
     println!("==============================");
 
+    #[cfg(unix)]
+    if daemon::run_remote(&command).await? {
+        return Ok(());
+    }
+
     let mut core = NetupiCore::new().await?;
+    let hooks = HookRunner::spawn(&core);
+    // The daemon also prints alerts, so it runs a notifier of its own.
+    let notifier = match command {
+        #[cfg(unix)]
+        Commands::Daemon => None,
+        _ => Some(Notifier::spawn(&core, notify::default_sinks())),
+    };
 
-    match command {
-        Commands::Interactive => unreachable!(), // handled above
-        Commands::Work => start_timer(&mut core, TimerType::PomodoroWork, "work session").await,
+    let result = match command {
+        Commands::Interactive
+        | Commands::Tui
+        | Commands::Completions { .. }
+        | Commands::Man { .. } => {
+            unreachable!() // handled above
+        }
+        Commands::Work {
+            project,
+            description,
+            preset,
+            at,
+        } => {
+            let at = parse_time_arg(at.as_deref()).await?;
+            start_pomodoro_timer(&mut core, project, description, preset, at).await
+        }
         Commands::Break => {
             start_timer(&mut core, TimerType::PomodoroShortBreak, "short break").await
         }
@@ -103,19 +474,186 @@ async fn handle_single_command(command: Commands) -> Result<(), PersistenceError
             start_timer(&mut core, TimerType::PomodoroLongBreak, "long break").await
         }
         Commands::Stopwatch => start_timer(&mut core, TimerType::Stopwatch, "stopwatch").await,
+        Commands::Timer { duration, label } => {
+            let label = (!label.is_empty()).then(|| label.join(" "));
+            start_countdown(&mut core, duration, label).await
+        }
         Commands::TimeTrack {
             project,
             description,
-        } => start_project_timer(&mut core, project, description).await,
-        Commands::Stop => stop_current_timer(&mut core).await,
-        Commands::Log => show_work_log(&mut core).await,
+            billable,
+            non_billable,
+            rate,
+            at,
+        } => {
+            let billable = if billable {
+                Some(true)
+            } else if non_billable {
+                Some(false)
+            } else {
+                None
+            };
+            let at = parse_time_arg(at.as_deref()).await?;
+            start_project_timer(&mut core, project, description, billable, rate, at).await
+        }
+        Commands::Continue { n } => {
+            let extended = core.continue_session(n as usize).await?;
+            println!("{}", continued_message(extended, &core.status().await?));
+            println!("Timer started! Press Ctrl+C to stop.");
+            show_clock(&mut core).await
+        }
+        Commands::Stop { at } => {
+            let at = parse_time_arg(at.as_deref()).await?;
+            stop_current_timer(&mut core, at).await
+        }
+        Commands::Pause => {
+            print_paused(core.timer().pause_timer().await);
+            Ok(())
+        }
+        Commands::Resume => {
+            print_resumed(core.timer().resume_timer().await);
+            Ok(())
+        }
+        #[cfg(unix)]
+        Commands::Daemon => daemon::run(core).await,
+        #[cfg(feature = "http-api")]
+        Commands::Serve { port } => http::run(core, port).await,
+        Commands::Log { since } => {
+            let since = match since {
+                Some(since) => parse_time(&since).await?,
+                None => {
+                    let days = core.timer().get_config().await.report_days;
+                    let calendar = core.calendar().await?;
+                    let first_day = calendar.today() - chrono::Duration::days(days as i64 - 1);
+                    calendar.day_start(first_day)
+                }
+            };
+            show_work_log(&mut core, since).await
+        }
         Commands::Projects => show_projects(&mut core).await,
         Commands::Today => show_today_summary(&mut core).await,
+        Commands::Week { offset } => show_period(&mut core, PeriodKind::Week, offset).await,
+        Commands::Month { offset } => show_period(&mut core, PeriodKind::Month, offset).await,
         Commands::Project { project } => show_project_details(&mut core, project).await,
         Commands::DeleteProject { project } => delete_project(&mut core, project).await,
-        Commands::Report { project, days } => generate_report(&mut core, project, days).await,
-        Commands::Status => show_status(&mut core).await,
+        Commands::SetProject {
+            project,
+            billable,
+            rate,
+            currency,
+            round,
+            rounding,
+            round_per,
+            budget,
+            budget_period,
+            clear_budget,
+        } => {
+            let rounding = round.map(|increment| {
+                Rounding::new(rounding.unwrap_or(RoundingMode::Up), increment)
+                    .with_scope(round_per.unwrap_or_default())
+            });
+            let budget = if clear_budget {
+                Some(None)
+            } else {
+                budget.map(|limit| {
+                    Some(Budget {
+                        limit,
+                        period: budget_period.unwrap_or_default(),
+                    })
+                })
+            };
+            set_project(
+                &mut core, project, billable, rate, currency, rounding, budget,
+            )
+            .await
+        }
+        Commands::Report {
+            project,
+            days,
+            from,
+            to,
+        } => {
+            if from.is_some() || to.is_some() {
+                let from = parse_day_arg(from.as_deref()).await?;
+                let to = parse_day_arg(to.as_deref()).await?;
+                generate_report(&mut core, project, from, to, &range_label(from, to)).await
+            } else {
+                let days = days.unwrap_or(core.timer().get_config().await.report_days);
+                let today = core.calendar().await?.today();
+                let from = today - chrono::Duration::days(days.saturating_sub(1) as i64);
+                let period = format!("last {} days", days);
+                generate_report(&mut core, project, Some(from), None, &period).await
+            }
+        }
+        Commands::Status { .. } => show_status(&mut core).await,
+        Commands::Goals {
+            daily_hours,
+            daily_pomodoros,
+            weekly_hours,
+            weekdays_only,
+        } => {
+            update_goals(
+                &mut core,
+                daily_hours,
+                daily_pomodoros,
+                weekly_hours,
+                weekdays_only,
+            )
+            .await?;
+            show_goals(&mut core).await
+        }
+        Commands::Stats { days } => {
+            let days = days.unwrap_or(core.timer().get_config().await.report_days);
+            show_stats(&mut core, days).await
+        }
+        Commands::Chart {
+            view,
+            period,
+            offset,
+        } => show_chart(&mut core, view, period, offset).await,
+        Commands::Calendar {
+            timezone,
+            day_starts_at,
+            week_start,
+        } => update_calendar(&mut core, timezone, day_starts_at, week_start).await,
+        Commands::Config { action } => update_config(&mut core, action).await,
+        Commands::Preset { name, save, delete } => {
+            update_presets(&mut core, name, save, delete).await
+        }
+        Commands::Invoice {
+            project,
+            from,
+            to,
+            group_by,
+            format,
+            round,
+            rounding,
+            round_per,
+            number,
+            output,
+            dry_run,
+        } => {
+            let rounding = round.map(|increment| {
+                Rounding::new(rounding.unwrap_or(RoundingMode::Up), increment)
+                    .with_scope(round_per.unwrap_or_default())
+            });
+            let options = InvoiceOptions {
+                project,
+                from: parse_day_arg(from.as_deref()).await?,
+                to: parse_day_arg(to.as_deref()).await?,
+                grouping: group_by,
+                rounding,
+                number,
+                dry_run,
+            };
+            generate_invoice(&mut core, options, format, output).await
+        }
+    };
+    hooks.finish().await;
+    if let Some(notifier) = notifier {
+        notifier.finish().await;
     }
+    result
 }
 
 async fn start_timer(
@@ -123,10 +661,23 @@ async fn start_timer(
     timer_type: TimerType,
     name: &str,
 ) -> Result<(), PersistenceError> {
-    println!("Starting {}...", name);
     core.timer().start_timer(timer_type).await?;
+    show_timer(core, name).await
+}
+
+/// Show the running timer until Ctrl+C.
+async fn show_timer(core: &mut NetupiCore, name: &str) -> Result<(), PersistenceError> {
+    match core.timer().get_current_state().await.target_duration {
+        Some(length) => println!("Starting {} {}...", length_label(length), name),
+        None => println!("Starting {}...", name),
+    }
 
     println!("Timer started! Press Ctrl+C to stop.");
+    show_clock(core).await
+}
+
+/// Show the running timer's elapsed time until Ctrl+C.
+async fn show_clock(core: &mut NetupiCore) -> Result<(), PersistenceError> {
     loop {
         let state = core.timer().get_current_state().await;
         let minutes = state.elapsed.num_minutes();
@@ -139,10 +690,86 @@ async fn start_timer(
     }
 }
 
+async fn start_countdown(
+    core: &mut NetupiCore,
+    duration: chrono::Duration,
+    label: Option<String>,
+) -> Result<(), PersistenceError> {
+    match &label {
+        Some(label) => println!("⏳ Starting {} timer: {}", format_clock(duration), label),
+        None => println!("⏳ Starting {} timer", format_clock(duration)),
+    }
+    let mut events = core.timer().subscribe_events();
+    core.start_countdown(duration, label).await?;
+
+    println!("Timer started! Press Ctrl+C to stop.");
+    loop {
+        while let Ok(event) = events.try_recv() {
+            if let TimerEvent::Completed { timer_type, label } = event {
+                println!("\n{}", completion_message(timer_type, label.as_deref()));
+                println!("💾 Session saved successfully.");
+                return Ok(());
+            }
+        }
+
+        let state = core.timer().get_current_state().await;
+        let remaining = state.remaining().unwrap_or_default();
+        print!("\r⏳ {} remaining ", format_clock(remaining));
+        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+
+        sleep(TokioDuration::from_millis(250)).await;
+    }
+}
+
+async fn start_pomodoro_timer(
+    core: &mut NetupiCore,
+    project: Option<String>,
+    description: Option<String>,
+    preset: Option<String>,
+    at: Option<DateTime<Utc>>,
+) -> Result<(), PersistenceError> {
+    let request = StartRequest::Pomodoro {
+        project: project.clone(),
+        description: description.clone(),
+        preset,
+    };
+    match at {
+        Some(at) => core.start_at(request, at).await?,
+        None => core.start(request).await?,
+    }
+    let Some(project) = project else {
+        return show_timer(core, "work session").await;
+    };
+
+    let length = core.timer().get_current_state().await.target_duration;
+    println!(
+        "🍅 Starting {} Pomodoro for project: {}",
+        length.map(length_label).unwrap_or_default(),
+        project
+    );
+    if let Some(desc) = &description {
+        println!("📝 Description: {}", desc);
+    }
+    println!("Timer started! Press Ctrl+C to stop.");
+    loop {
+        let state = core.timer().get_current_state().await;
+        let minutes = state.elapsed.num_minutes();
+        let seconds = state.elapsed.num_seconds() % 60;
+
+        print!("\r🍅 {} | {:02}:{:02}", project, minutes, seconds);
+        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+
+        sleep(TokioDuration::from_secs(1)).await;
+    }
+}
+
 async fn start_project_timer(
     core: &mut NetupiCore,
     project: String,
     description: Option<String>,
+    billable: Option<bool>,
+    rate: Option<f64>,
+    at: Option<DateTime<Utc>>,
 ) -> Result<(), PersistenceError> {
     println!("🚀 Starting work session for project: {}", project);
     if let Some(desc) = &description {
@@ -150,10 +777,39 @@ async fn start_project_timer(
     }
     let project_display = project.clone();
 
-    core.start_work_session(project, description).await?;
+    let request = StartRequest::Project {
+        project,
+        description,
+        billable,
+        rate,
+    };
+    match at {
+        Some(at) => core.start_at(request, at).await?,
+        None => core.start(request).await?,
+    }
+    if billable.is_some() || rate.is_some() {
+        match billable {
+            Some(true) => println!("💵 Billable session"),
+            Some(false) => println!("🆓 Non-billable session"),
+            None => {}
+        }
+        if let Some(rate) = rate {
+            println!("💵 Rate: {:.2}/h", rate);
+        }
+    }
     println!("⏰ Work timer started! Use 'netupi stop' to finish and save your session.");
+    if let Some(status) = core.get_budget_status(&project_display).await? {
+        println!("📊 Budget: {}", format_budget_status(&status));
+    }
 
+    let mut events = core.timer().subscribe_events();
     loop {
+        while let Ok(event) = events.try_recv() {
+            if let TimerEvent::BudgetThreshold { project, percent } = event {
+                println!("\n{}", budget_alert(&project, percent));
+            }
+        }
+
         let state = core.timer().get_current_state().await;
         let minutes = state.elapsed.num_minutes();
         let seconds = state.elapsed.num_seconds() % 60;
@@ -177,20 +833,62 @@ async fn start_project_timer(
     }
 }
 
-async fn stop_current_timer(core: &mut NetupiCore) -> Result<(), PersistenceError> {
-    let state = core.timer().get_current_state().await;
+async fn stop_current_timer(
+    core: &mut NetupiCore,
+    at: Option<DateTime<Utc>>,
+) -> Result<(), PersistenceError> {
+    match core.stop_at(at.unwrap_or_else(Utc::now)).await? {
+        Some(status) => print_stopped(status.timer.elapsed),
+        None => println!("⏸️  No timer is currently running."),
+    }
+    Ok(())
+}
+
+/// The configured calendar, for reading times given on the command line
+/// without loading the core.
+async fn config_calendar() -> Result<Calendar, PersistenceError> {
+    let config = PersistenceManager::new()?.load_config().await?;
+    Calendar::from_config(&config.calendar)
+}
 
-    if state.state == TimerState::Idle {
-        println!("⏸️  No timer is currently running.");
-        return Ok(());
+/// Parse a time given on the command line, e.g. `--at "10 minutes ago"`.
+async fn parse_time(input: &str) -> Result<DateTime<Utc>, PersistenceError> {
+    when::parse_instant(input, Utc::now(), &config_calendar().await?)
+        .map_err(PersistenceError::Config)
+}
+
+async fn parse_time_arg(input: Option<&str>) -> Result<Option<DateTime<Utc>>, PersistenceError> {
+    match input {
+        Some(input) => parse_time(input).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Parse a day given on the command line, e.g. `--from "last monday"`.
+async fn parse_day_arg(input: Option<&str>) -> Result<Option<NaiveDate>, PersistenceError> {
+    let Some(input) = input else {
+        return Ok(None);
+    };
+    when::parse_day(input, Utc::now(), &config_calendar().await?)
+        .map(Some)
+        .map_err(PersistenceError::Config)
+}
+
+/// A report period like "2026-03-02 – 2026-03-08" or "since 2026-03-02".
+fn range_label(from: Option<NaiveDate>, to: Option<NaiveDate>) -> String {
+    match (from, to) {
+        (Some(from), Some(to)) => format!("{} – {}", from, to),
+        (Some(from), None) => format!("since {}", from),
+        (None, Some(to)) => format!("until {}", to),
+        (None, None) => "all time".to_string(),
     }
+}
 
-    let total_minutes = state.elapsed.num_minutes();
+fn print_stopped(elapsed: chrono::Duration) {
+    let total_minutes = elapsed.num_minutes();
     let hours = total_minutes / 60;
     let minutes = total_minutes % 60;
 
-    core.timer().stop_timer().await?;
-
     println!("✅ Timer stopped!");
     if hours > 0 {
         println!("⏱️  Total time: {} hours {} minutes", hours, minutes);
@@ -198,25 +896,94 @@ async fn stop_current_timer(core: &mut NetupiCore) -> Result<(), PersistenceErro
         println!("⏱️  Total time: {} minutes", minutes);
     }
     println!("💾 Session saved successfully.");
+}
 
-    Ok(())
+fn print_paused(paused: bool) {
+    if paused {
+        println!("⏸️  Timer paused. Use 'netupi resume' to continue.");
+    } else {
+        println!("ℹ️  No running timer to pause.");
+    }
+}
+
+fn print_resumed(resumed: bool) {
+    if resumed {
+        println!("▶️  Timer resumed.");
+    } else {
+        println!("ℹ️  No paused timer to resume.");
+    }
 }
 
 async fn show_status(core: &mut NetupiCore) -> Result<(), PersistenceError> {
-    let state = core.timer().get_current_state().await;
-    println!("Timer Type: {:?}", state.timer_type);
-    println!("State: {:?}", state.state);
-    println!(
-        "Elapsed: {:02}:{:02}",
-        state.elapsed.num_minutes(),
-        state.elapsed.num_seconds() % 60
-    );
+    for line in status_lines(&core.active_status().await?) {
+        println!("{}", line);
+    }
     Ok(())
 }
 
-async fn show_work_log(_core: &mut NetupiCore) -> Result<(), PersistenceError> {
-    println!("📊 Work Log (Coming in next iteration)");
-    println!("This will show your work time statistics.");
+async fn show_work_log(
+    core: &mut NetupiCore,
+    since: DateTime<Utc>,
+) -> Result<(), PersistenceError> {
+    let calendar = core.calendar().await?;
+    let mut sessions: Vec<WorkSession> = core
+        .get_sessions()
+        .await?
+        .into_iter()
+        .filter(|s| s.end_time.is_some_and(|end| end > since))
+        .collect();
+    sessions.sort_by_key(|s| s.start_time);
+
+    println!(
+        "📊 Work Log since {}",
+        calendar.local(since).format("%Y-%m-%d %H:%M")
+    );
+    println!("==============================");
+    if sessions.is_empty() {
+        println!("No work sessions in this period.");
+        println!();
+        return Ok(());
+    }
+
+    let mut day = None;
+    for session in &sessions {
+        let start = calendar.local(session.start_time);
+        let session_day = calendar.day_of(session.start_time);
+        if day != Some(session_day) {
+            println!("\n{}", session_day.format("%A %Y-%m-%d"));
+            day = Some(session_day);
+        }
+        let end = session
+            .end_time
+            .map(|end| calendar.local(end).format("%H:%M").to_string())
+            .unwrap_or_default();
+        let marker = match session.session_type {
+            TimerType::PomodoroWork => "🍅",
+            TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak => "☕",
+            TimerType::CustomTimer => "⏳",
+            TimerType::Stopwatch => "⏱️ ",
+        };
+        let mut line = format!(
+            "  {}–{} {:>8}  {} {}",
+            start.format("%H:%M"),
+            end,
+            format_duration(session.duration),
+            marker,
+            session.project().unwrap_or("—")
+        );
+        if let Some(description) = &session.description {
+            line.push_str(&format!(": {}", description));
+        }
+        println!("{}", line);
+    }
+
+    let total: chrono::Duration = sessions
+        .iter()
+        .filter(|s| s.session_type.is_work())
+        .map(|s| s.duration)
+        .sum();
+    println!("\nTotal work time: {}", format_duration(total));
+    println!();
     Ok(())
 }
 
@@ -224,22 +991,20 @@ async fn show_projects(core: &mut NetupiCore) -> Result<(), PersistenceError> {
     println!("📂 Your Projects:");
     println!("==================");
 
-    match core.get_projects().await {
+    match core.get_project_summaries().await {
         Ok(projects) => {
             if projects.is_empty() {
                 println!(
                     "No projects yet. Start working on one with 'netupi time-track <project>'!"
                 );
             } else {
-                for (project, duration) in projects {
-                    let total_minutes = duration.num_minutes();
-                    let hours = total_minutes / 60;
-                    let minutes = total_minutes % 60;
-                    if hours > 0 {
-                        println!("{}: {} hours {} minutes", project, hours, minutes);
-                    } else {
-                        println!("{}: {} minutes", project, minutes);
-                    }
+                for summary in projects {
+                    println!(
+                        "{}: {}{}",
+                        summary.name,
+                        format_summary_time(&summary),
+                        billing_suffix(&summary)
+                    );
                 }
             }
         }
@@ -256,23 +1021,33 @@ async fn show_today_summary(core: &mut NetupiCore) -> Result<(), PersistenceErro
     println!("📅 Today's Work Summary:");
     println!("======================");
 
-    match core.get_today_summary().await {
-        Ok(summary) => {
-            let mut today_projects: Vec<(String, chrono::Duration)> = summary.into_iter().collect();
-            today_projects.sort_by(|a, b| a.0.cmp(&b.0));
+    let daily_goals: Vec<_> = core
+        .get_goal_progress()
+        .await?
+        .into_iter()
+        .filter(|g| g.kind != netupi_core::goals::GoalKind::WeeklyFocus)
+        .collect();
+    for goal in &daily_goals {
+        for line in goal_lines(goal) {
+            println!("{}", line);
+        }
+    }
+    if !daily_goals.is_empty() {
+        println!();
+    }
 
+    match core.get_today_project_summaries().await {
+        Ok(today_projects) => {
             if today_projects.is_empty() {
                 println!("No work sessions today yet. Get started with 'netupi work <project>'!");
             } else {
-                for (project, duration) in today_projects {
-                    let total_minutes = duration.num_minutes();
-                    let hours = total_minutes / 60;
-                    let minutes = total_minutes % 60;
-                    if hours > 0 {
-                        println!("{}: {} hours {} minutes", project, hours, minutes);
-                    } else {
-                        println!("{}: {} minutes", project, minutes);
-                    }
+                for summary in today_projects {
+                    println!(
+                        "{}: {}{}",
+                        summary.name,
+                        format_summary_time(&summary),
+                        billing_suffix(&summary)
+                    );
                 }
             }
         }
@@ -285,6 +1060,80 @@ async fn show_today_summary(core: &mut NetupiCore) -> Result<(), PersistenceErro
     Ok(())
 }
 
+async fn show_stats(core: &mut NetupiCore, days: u32) -> Result<(), PersistenceError> {
+    let title = format!("🍅 Pomodoro Stats (last {} days)", days.max(1));
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count() + 1));
+
+    let stats = core.get_pomodoro_stats(days).await?;
+    if stats.completed + stats.abandoned == 0 && stats.best_hour().is_none() {
+        println!("No Pomodoros or work sessions yet. Start one with 'netupi work'!");
+    } else {
+        for line in stats_lines(&stats) {
+            println!("{}", line);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+async fn show_chart(
+    core: &mut NetupiCore,
+    view: Option<ChartView>,
+    period: PeriodKind,
+    offset: u32,
+) -> Result<(), PersistenceError> {
+    if view != Some(ChartView::Heatmap) {
+        let summary = core.get_period_summary(period, offset).await?;
+        let title = format!("📊 {}", period_title(&summary));
+        println!("{}", title);
+        println!("{}", "=".repeat(title.chars().count() + 1));
+        let width = core.timer().get_config().await.display.chart_width;
+        for line in chart::bar_chart(&summary, width) {
+            println!("{}", line);
+        }
+        println!();
+    }
+
+    if view != Some(ChartView::Bars) {
+        let calendar = core.calendar().await?;
+        let today = calendar.today();
+        let start = calendar.week_of(today) - chrono::Duration::weeks(52);
+        let totals = core
+            .get_daily_totals(start, today + chrono::Duration::days(1))
+            .await?;
+        println!("🗓️  Past Year:");
+        println!("==============");
+        for line in chart::heatmap(start, &totals) {
+            println!("{}", line);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+async fn show_period(
+    core: &mut NetupiCore,
+    kind: PeriodKind,
+    offset: u32,
+) -> Result<(), PersistenceError> {
+    let current = core.get_period_summary(kind, offset).await?;
+    let previous = core.get_summary_for(current.period.previous()).await?;
+
+    let title = format!("📅 {}", period_title(&current));
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count() + 1));
+    if current.projects.is_empty() && previous.projects.is_empty() {
+        println!("No work sessions in this {} or the previous one.", kind);
+    } else {
+        for line in period_table(&current, &previous) {
+            println!("{}", line);
+        }
+    }
+    println!();
+    Ok(())
+}
+
 async fn show_project_details(core: &mut NetupiCore, project: String) -> Result<(), PersistenceError> {
     let sessions = core.get_sessions_for_project(&project).await?;
 
@@ -293,6 +1142,10 @@ async fn show_project_details(core: &mut NetupiCore, project: String) -> Result<
         return Ok(());
     }
 
+    let settings = core.get_project_settings(&project).await?;
+    let summary = core.get_report(None, Some(&project)).await?;
+    let calendar = core.calendar().await?;
+
     // Calculate total duration
     let total_duration: chrono::Duration = sessions.iter().map(|s| s.duration).sum();
     let total_minutes = total_duration.num_minutes();
@@ -306,11 +1159,29 @@ async fn show_project_details(core: &mut NetupiCore, project: String) -> Result<
     } else {
         println!("Total time: {} minutes", total_mins);
     }
+    for summary in &summary {
+        for line in project_billing_lines(summary, &settings) {
+            println!("{}", line);
+        }
+    }
+    if let Some(status) = core.get_budget_status(&project).await? {
+        println!("Budget: {}", format_budget_status(&status));
+    }
     println!("\nSessions:");
     for session in sessions {
-        let start_str = session.start_time.format("%Y-%m-%d %H:%M").to_string();
+        let billable_marker = if billing::is_billable(&session, Some(&settings)) {
+            " 💵"
+        } else {
+            ""
+        };
+        let pomodoro_marker = if session.session_type == TimerType::PomodoroWork {
+            " 🍅"
+        } else {
+            ""
+        };
+        let start_str = calendar.local(session.start_time).format("%Y-%m-%d %H:%M").to_string();
         let end_str = session.end_time
-            .map(|end| end.format("%Y-%m-%d %H:%M").to_string())
+            .map(|end| calendar.local(end).format("%Y-%m-%d %H:%M").to_string())
             .unwrap_or("Ongoing".to_string());
         let dur_mins = session.duration.num_minutes();
         let dur_str = if dur_mins >= 60 {
@@ -321,10 +1192,12 @@ async fn show_project_details(core: &mut NetupiCore, project: String) -> Result<
             format!("{} minutes", dur_mins)
         };
         println!(
-            "- {}: End: {} ({}) | Description: {}",
+            "- {}: End: {} ({}){}{} | Description: {}",
             start_str,
             end_str,
             dur_str,
+            pomodoro_marker,
+            billable_marker,
             session.description.as_deref().unwrap_or("None")
         );
     }
@@ -332,6 +1205,55 @@ async fn show_project_details(core: &mut NetupiCore, project: String) -> Result<
     Ok(())
 }
 
+async fn set_project(
+    core: &mut NetupiCore,
+    project: String,
+    billable: Option<bool>,
+    rate: Option<f64>,
+    currency: Option<String>,
+    rounding: Option<Rounding>,
+    budget: Option<Option<Budget>>,
+) -> Result<(), PersistenceError> {
+    let mut settings = core.get_project_settings(&project).await?;
+    if let Some(billable) = billable {
+        settings.billable = billable;
+    }
+    if let Some(rate) = rate {
+        settings.hourly_rate = Some(rate);
+    }
+    if let Some(currency) = currency {
+        settings.currency = Some(currency.to_uppercase());
+    }
+    if rounding.is_some() {
+        settings.rounding = rounding;
+    }
+    if let Some(budget) = budget {
+        settings.budget = budget;
+    }
+    core.set_project_settings(&project, settings.clone())
+        .await?;
+
+    println!("✅ Updated project '{}'", project);
+    println!(
+        "   Billable: {}",
+        if settings.billable { "yes" } else { "no" }
+    );
+    if let Some(rate) = settings.hourly_rate {
+        println!(
+            "   Rate: {}/h",
+            format_money(rate, &billing::currency(Some(&settings)))
+        );
+    }
+    if let Some(rounding) = settings.rounding {
+        println!("   Rounding: {}", rounding);
+    }
+    if let Some(status) = core.get_budget_status(&project).await? {
+        println!("   Budget: {}", format_budget_status(&status));
+    }
+    println!();
+    Ok(())
+}
+
 
 async fn delete_project(core: &mut NetupiCore, project: String) -> Result<(), PersistenceError> {
     println!("⚠️  Deleting all sessions for '{}' (irreversible).", project);
@@ -352,14 +1274,261 @@ async fn delete_project(core: &mut NetupiCore, project: String) -> Result<(), Pe
 
 
 async fn generate_report(
-    _core: &mut NetupiCore,
+    core: &mut NetupiCore,
     project: Option<String>,
-    days: u32,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    period: &str,
 ) -> Result<(), PersistenceError> {
-    match project {
-        Some(proj) => println!("📈 Report for project '{}' (last {} days)", proj, days),
-        None => println!("📈 Work Report (last {} days)", days),
+    match &project {
+        Some(proj) => println!("📈 Report for project '{}' ({})", proj, period),
+        None => println!("📈 Work Report ({})", period),
+    }
+    println!("==============================");
+
+    let summaries = core
+        .get_report_between(from, to, project.as_deref())
+        .await?;
+    if summaries.is_empty() {
+        println!("No work sessions in this period.");
+        println!();
+        return Ok(());
+    }
+
+    for summary in &summaries {
+        println!(
+            "{}: {}{}",
+            summary.name,
+            format_summary_time(summary),
+            billing_suffix(summary)
+        );
+    }
+
+    let total: chrono::Duration = summaries.iter().map(|s| s.total).sum();
+    let rounded: chrono::Duration = summaries.iter().map(|s| s.rounded).sum();
+    let billable: chrono::Duration = summaries.iter().map(|s| s.billable).sum();
+    println!();
+    println!("Total time: {}", format_rounded(rounded, total));
+    if !billable.is_zero() {
+        println!("Billable time: {}", format_duration(billable));
+        for (currency, amount) in billing::earnings_by_currency(&summaries) {
+            println!("Earnings: {}", format_money(amount, &currency));
+        }
+    }
+
+    let mut budgets = Vec::new();
+    for summary in &summaries {
+        if let Some(status) = core.get_budget_status(&summary.name).await? {
+            budgets.push((summary.name.clone(), status));
+        }
+    }
+    if !budgets.is_empty() {
+        println!("\nBudgets:");
+        for (project, status) in budgets {
+            println!("- {}: {}", project, format_budget_status(&status));
+        }
     }
-    println!("This will generate detailed work reports.");
+    println!();
+    Ok(())
+}
+
+async fn generate_invoice(
+    core: &mut NetupiCore,
+    options: InvoiceOptions,
+    format: InvoiceFormat,
+    output: Option<std::path::PathBuf>,
+) -> Result<(), PersistenceError> {
+    let (invoice, rendered) = core.create_invoice(&options, format).await?;
+
+    if invoice.lines.is_empty() {
+        println!(
+            "ℹ️  No uninvoiced billable sessions for '{}' in this period.",
+            options.project
+        );
+        return Ok(());
+    }
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            println!(
+                "🧾 Invoice {} written to {}",
+                invoice.number,
+                path.display()
+            );
+        }
+        None => println!("{}", rendered),
+    }
+
+    println!(
+        "💵 Total: {} → {}",
+        format_duration(invoice.total_duration),
+        format_money(invoice.total_amount, &invoice.currency)
+    );
+    if options.dry_run {
+        println!("👀 Dry run: sessions were not marked as invoiced.");
+    } else {
+        println!(
+            "✅ Marked {} session(s) as invoiced on {}.",
+            invoice.session_ids.len(),
+            invoice.number
+        );
+    }
+    println!();
+    Ok(())
+}
+
+fn hours_goal(hours: chrono::Duration) -> Option<chrono::Duration> {
+    (hours > chrono::Duration::zero()).then_some(hours)
+}
+
+async fn update_goals(
+    core: &mut NetupiCore,
+    daily_hours: Option<chrono::Duration>,
+    daily_pomodoros: Option<u32>,
+    weekly_hours: Option<chrono::Duration>,
+    weekdays_only: Option<bool>,
+) -> Result<(), PersistenceError> {
+    if daily_hours.is_none()
+        && daily_pomodoros.is_none()
+        && weekly_hours.is_none()
+        && weekdays_only.is_none()
+    {
+        return Ok(());
+    }
+
+    let mut config = core.timer().get_config().await;
+    if let Some(hours) = daily_hours {
+        config.goals.daily_focus = hours_goal(hours);
+    }
+    if let Some(count) = daily_pomodoros {
+        config.goals.daily_pomodoros = (count > 0).then_some(count);
+    }
+    if let Some(hours) = weekly_hours {
+        config.goals.weekly_focus = hours_goal(hours);
+    }
+    if let Some(weekdays_only) = weekdays_only {
+        config.goals.weekdays_only = weekdays_only;
+    }
+    core.timer().set_config(config).await?;
+    println!("✅ Goals updated.");
+    Ok(())
+}
+
+async fn update_config(
+    core: &mut NetupiCore,
+    action: Option<ConfigAction>,
+) -> Result<(), PersistenceError> {
+    let mut config = core.timer().get_config().await;
+    match action.unwrap_or(ConfigAction::List) {
+        ConfigAction::List => {
+            println!("⚙️  Settings:");
+            println!("============");
+            for line in config_lines(&config) {
+                println!("{}", line);
+            }
+            println!();
+        }
+        ConfigAction::Get { key } => println!("{} = {}", key, config.get(&key)?),
+        ConfigAction::Set { key, value } => {
+            config.set(&key, &value)?;
+            core.timer().set_config(config.clone()).await?;
+            println!("✅ {} = {}", key, config.get(&key)?);
+        }
+        ConfigAction::Reset { key: Some(key) } => {
+            config.reset(&key)?;
+            core.timer().set_config(config.clone()).await?;
+            println!("✅ {} reset to {}", key, config.get(&key)?);
+        }
+        ConfigAction::Reset { key: None } => {
+            let config = TimerConfig::default();
+            core.timer().set_config(config.clone()).await?;
+            apply_display_config(&config.display);
+            println!("✅ All settings reset to their defaults.");
+        }
+    }
+    Ok(())
+}
+
+async fn update_presets(
+    core: &mut NetupiCore,
+    name: Option<String>,
+    save: Option<String>,
+    delete: Option<String>,
+) -> Result<(), PersistenceError> {
+    if let Some(name) = name {
+        core.use_preset(&name).await?;
+        println!("✅ Switched to the {} preset.", name);
+    }
+    if let Some(name) = save {
+        let mut config = core.timer().get_config().await;
+        config.save_preset(&name)?;
+        core.timer().set_config(config).await?;
+        println!("✅ Saved the current timer lengths as {}.", name);
+    }
+    if let Some(name) = delete {
+        let mut config = core.timer().get_config().await;
+        config.remove_preset(&name)?;
+        core.timer().set_config(config).await?;
+        println!("🗑️  Deleted the {} preset.", name);
+    }
+
+    let config = core.timer().get_config().await;
+    println!("🍅 Presets:");
+    println!("===========");
+    for line in preset_lines(&config) {
+        println!("{}", line);
+    }
+    if config.presets.is_empty() {
+        println!("No presets yet. Save one with: netupi preset --save <name>");
+    }
+    println!();
+    Ok(())
+}
+
+async fn update_calendar(
+    core: &mut NetupiCore,
+    timezone: Option<String>,
+    day_starts_at: Option<chrono::NaiveTime>,
+    week_start: Option<chrono::Weekday>,
+) -> Result<(), PersistenceError> {
+    let mut config = core.timer().get_config().await;
+    if timezone.is_some() || day_starts_at.is_some() || week_start.is_some() {
+        if let Some(timezone) = timezone {
+            config.calendar.timezone = (timezone != "system").then_some(timezone);
+        }
+        if let Some(day_starts_at) = day_starts_at {
+            config.calendar.day_starts_at = day_starts_at;
+        }
+        if let Some(week_start) = week_start {
+            config.calendar.week_starts_on = week_start;
+        }
+        core.timer().set_config(config.clone()).await?;
+        println!("✅ Calendar updated.");
+    }
+
+    println!("📅 Calendar:");
+    println!("============");
+    for line in calendar_lines(&config.calendar) {
+        println!("{}", line);
+    }
+    println!();
+    Ok(())
+}
+
+async fn show_goals(core: &mut NetupiCore) -> Result<(), PersistenceError> {
+    println!("🎯 Your Goals:");
+    println!("==============");
+
+    let goals = core.get_goal_progress().await?;
+    if goals.is_empty() {
+        println!("No goals set. Try 'netupi goals --daily-hours 6 --daily-pomodoros 8'.");
+    }
+    for goal in goals {
+        for line in goal_lines(&goal) {
+            println!("{}", line);
+        }
+    }
+    println!();
     Ok(())
 }
@@ -0,0 +1,373 @@
+use crossterm::style::Stylize;
+use netupi_core::config;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::{Hint, Hinter, HistoryHinter};
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::borrow::Cow;
+
+/// Interactive commands and the arguments they take, for completion and hints.
+const COMMANDS: &[(&str, &str)] = &[
+    ("work", "<project> [description]"),
+    ("pomodoro", "[--preset <name>] [project] [description]"),
+    ("pomo", "[--preset <name>] [project] [description]"),
+    ("break", ""),
+    ("timer", "<duration> [label]"),
+    ("continue", "[n]"),
+    ("stop", ""),
+    ("status", ""),
+    ("pause", ""),
+    ("resume", ""),
+    ("projects", ""),
+    ("today", ""),
+    ("week", "[offset]"),
+    ("month", "[offset]"),
+    ("chart", "[week|month|heatmap] [offset]"),
+    ("stats", "[days]"),
+    ("project", "<name>"),
+    ("delete-project", "<name>"),
+    ("billable", "<name> <yes|no>"),
+    ("rate", "<name> <amount> [currency]"),
+    ("budget", "<name> <hours|off> [total|weekly]"),
+    ("goals", ""),
+    ("goal", "<daily|weekly|pomodoros|weekdays> <value|off>"),
+    ("calendar", "[timezone|day-start|week-start] [value]"),
+    ("config", "[list|get|set|reset] [key] [value]"),
+    ("preset", "[name|save <name>|delete <name>]"),
+    ("help", ""),
+    ("clear", ""),
+    ("exit", ""),
+    ("quit", ""),
+];
+
+/// A word of a command line and the byte offset it starts at.
+struct Word {
+    text: String,
+    start: usize,
+}
+
+/// A command line split into words.
+struct Words {
+    words: Vec<Word>,
+    /// The quote left open at the end of the line, if any.
+    open_quote: Option<char>,
+    /// Whether the last word was finished, i.e. followed by whitespace.
+    finished: bool,
+}
+
+/// Split a line like a shell does: words are separated by whitespace,
+/// 'single quotes' keep everything as it is, "double quotes" keep
+/// everything but `\"` and `\\`, and a backslash outside quotes escapes
+/// the next character.
+fn lex(line: &str) -> Words {
+    let mut words = Vec::new();
+    let mut current: Option<Word> = None;
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if quote.is_none() && c.is_whitespace() {
+            words.extend(current.take());
+            continue;
+        }
+        let word = current.get_or_insert_with(|| Word {
+            text: String::new(),
+            start: i,
+        });
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') if matches!(chars.peek(), Some((_, '"' | '\\'))) => {
+                word.text.extend(chars.next().map(|(_, c)| c));
+            }
+            (Some(_), c) => word.text.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '\\') => match chars.next() {
+                Some((_, c)) => word.text.push(c),
+                None => word.text.push('\\'),
+            },
+            (None, c) => word.text.push(c),
+        }
+    }
+    let finished = current.is_none();
+    words.extend(current);
+    Words {
+        words,
+        open_quote: quote,
+        finished,
+    }
+}
+
+/// The words of an interactive command line, with quotes and escapes
+/// resolved: `work "Mobile App" 'login screen'` gives `work`, `Mobile App`
+/// and `login screen`.
+pub fn split_args(line: &str) -> Result<Vec<String>, String> {
+    let words = lex(line);
+    if let Some(quote) = words.open_quote {
+        return Err(format!("Missing closing {} quote", quote));
+    }
+    Ok(words.words.into_iter().map(|word| word.text).collect())
+}
+
+/// `text` as one word of a command line, quoted if it has to be.
+fn quote(text: &str) -> String {
+    if !text.is_empty()
+        && !text
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\'))
+    {
+        return text.to_string();
+    }
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Completes commands, project and preset names, and the keywords commands
+/// take, with the rest of a command or its usage as an inline hint. Session
+/// ids aren't completed: no command takes one yet (`continue` counts back
+/// from the last session instead).
+#[derive(Default)]
+pub struct PromptHelper {
+    /// Projects with tracked time.
+    pub projects: Vec<String>,
+    pub presets: Vec<String>,
+    history: HistoryHinter,
+}
+
+impl PromptHelper {
+    /// Possible values for the word after `words`.
+    fn candidates(&self, words: &[String]) -> Vec<String> {
+        let owned = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        let Some((command, args)) = words.split_first() else {
+            return COMMANDS.iter().map(|(name, _)| name.to_string()).collect();
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match (command.to_lowercase().as_str(), args.as_slice()) {
+            ("pomodoro" | "pomo", [.., "--preset"]) => self.presets.clone(),
+            ("pomodoro" | "pomo", []) => {
+                let mut candidates = self.projects.clone();
+                candidates.push("--preset".to_string());
+                candidates
+            }
+            ("pomodoro" | "pomo", ["--preset", _]) => self.projects.clone(),
+            ("work" | "project" | "delete-project" | "billable" | "rate" | "budget", []) => {
+                self.projects.clone()
+            }
+            ("billable", [_]) => owned(&["yes", "no"]),
+            ("budget", [_]) => owned(&["off"]),
+            ("budget", [_, _]) => owned(&["total", "weekly"]),
+            ("chart", []) => owned(&["week", "month", "heatmap"]),
+            ("goal", []) => owned(&["daily", "weekly", "pomodoros", "weekdays"]),
+            ("calendar", []) => owned(&["timezone", "day-start", "week-start"]),
+            ("config", []) => owned(&["list", "get", "set", "reset"]),
+            ("config", ["get" | "set" | "reset"]) => config::KEYS
+                .iter()
+                .map(|key| key.name.to_string())
+                .collect(),
+            ("preset" | "presets", []) => {
+                let mut candidates = self.presets.clone();
+                candidates.extend(owned(&["save", "delete"]));
+                candidates
+            }
+            ("preset" | "presets", ["delete"]) => self.presets.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Completer for PromptHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let lexed = lex(&line[..pos]);
+        let mut words: Vec<String> = lexed.words.iter().map(|w| w.text.clone()).collect();
+        let (start, prefix) = match lexed.words.last() {
+            Some(word) if !lexed.finished => (word.start, words.pop().unwrap_or_default()),
+            _ => (pos, String::new()),
+        };
+        let prefix = prefix.to_lowercase();
+        let pairs = self
+            .candidates(&words)
+            .into_iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&prefix))
+            .map(|candidate| Pair {
+                replacement: format!("{} ", quote(&candidate)),
+                display: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+/// The rest of a command name, or a command's usage.
+pub struct PromptHint {
+    text: String,
+    /// Whether accepting the hint (→ at the end of the line) inserts it;
+    /// usage hints are only shown.
+    insert: bool,
+}
+
+impl Hint for PromptHint {
+    fn display(&self) -> &str {
+        &self.text
+    }
+
+    fn completion(&self) -> Option<&str> {
+        self.insert.then_some(self.text.as_str())
+    }
+}
+
+impl Hinter for PromptHelper {
+    type Hint = PromptHint;
+
+    fn hint(&self, line: &str, pos: usize, ctx: &Context<'_>) -> Option<PromptHint> {
+        if pos < line.len() {
+            return None;
+        }
+        let lexed = lex(line);
+        match lexed.words.as_slice() {
+            [word] if !lexed.finished && lexed.open_quote.is_none() => {
+                let mut matches = COMMANDS
+                    .iter()
+                    .filter(|(name, _)| name.starts_with(word.text.as_str()))
+                    .filter(|(name, _)| name.len() > word.text.len());
+                if let (Some((name, _)), None) = (matches.next(), matches.next()) {
+                    return Some(PromptHint {
+                        text: name[word.text.len()..].to_string(),
+                        insert: true,
+                    });
+                }
+            }
+            [word] if lexed.finished => {
+                let usage = COMMANDS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&word.text))
+                    .map(|(_, usage)| *usage)
+                    .filter(|usage| !usage.is_empty());
+                if let Some(usage) = usage {
+                    return Some(PromptHint {
+                        text: usage.to_string(),
+                        insert: false,
+                    });
+                }
+            }
+            _ => {}
+        }
+        self.history
+            .hint(line, pos, ctx)
+            .map(|text| PromptHint { text, insert: true })
+    }
+}
+
+impl Highlighter for PromptHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dark_grey().to_string())
+    }
+}
+
+impl Validator for PromptHelper {}
+
+impl Helper for PromptHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyline::history::DefaultHistory;
+
+    fn args(line: &str) -> Vec<String> {
+        split_args(line).unwrap()
+    }
+
+    #[test]
+    fn test_split_args_quotes_and_escapes() {
+        assert_eq!(args("  work   api  "), ["work", "api"]);
+        assert_eq!(
+            args(r#"work "Mobile App" 'login screen'"#),
+            ["work", "Mobile App", "login screen"]
+        );
+        // Single quotes keep backslashes; double quotes only escape " and \.
+        assert_eq!(args(r"work 'a\b'"), ["work", r"a\b"]);
+        assert_eq!(
+            args(r#"work "say \"hi\"" "c:\\x" "a\b""#),
+            ["work", r#"say "hi""#, r"c:\x", r"a\b"]
+        );
+        // Outside quotes a backslash escapes the next character.
+        assert_eq!(
+            args(r"work Mobile\ App it\'s"),
+            ["work", "Mobile App", "it's"]
+        );
+        assert_eq!(args(r"work trailing\"), ["work", r"trailing\"]);
+        // Quotes may start mid-word and leave an empty argument.
+        assert_eq!(args(r#"work api"-v2" """#), ["work", "api-v2", ""]);
+        assert_eq!(args("rate '' 80"), ["rate", "", "80"]);
+    }
+
+    #[test]
+    fn test_split_args_unclosed_quote() {
+        assert_eq!(
+            split_args(r#"work "Mobile App"#),
+            Err("Missing closing \" quote".to_string())
+        );
+        assert_eq!(
+            split_args("work 'Mobile"),
+            Err("Missing closing ' quote".to_string())
+        );
+    }
+
+    fn helper() -> PromptHelper {
+        PromptHelper {
+            projects: vec!["Mobile App".to_string(), "api".to_string()],
+            presets: vec!["classic".to_string(), "deep".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn candidates(line: &str) -> Vec<String> {
+        helper().candidates(&args(line))
+    }
+
+    #[test]
+    fn test_candidates() {
+        assert!(candidates("").contains(&"continue".to_string()));
+        assert_eq!(candidates("work"), ["Mobile App", "api"]);
+        assert!(candidates("work api").is_empty());
+        assert_eq!(candidates("pomo"), ["Mobile App", "api", "--preset"]);
+        assert_eq!(candidates("pomodoro --preset"), ["classic", "deep"]);
+        assert_eq!(candidates("pomodoro --preset deep"), ["Mobile App", "api"]);
+        assert_eq!(candidates("billable api"), ["yes", "no"]);
+        assert_eq!(candidates("budget api 10"), ["total", "weekly"]);
+        assert_eq!(candidates("preset"), ["classic", "deep", "save", "delete"]);
+        assert_eq!(candidates("preset delete"), ["classic", "deep"]);
+        assert!(candidates("config set").contains(&"timer.work".to_string()));
+        assert!(candidates("stop").is_empty());
+    }
+
+    #[test]
+    fn test_complete_quotes_replacements() {
+        let history = DefaultHistory::new();
+        let ctx = Context::new(&history);
+        let complete = |line: &str| {
+            let (start, pairs) = helper().complete(line, line.len(), &ctx).unwrap();
+            let replacements: Vec<String> = pairs.into_iter().map(|p| p.replacement).collect();
+            (start, replacements)
+        };
+
+        assert_eq!(complete("wo"), (0, vec!["work ".to_string()]));
+        assert_eq!(
+            complete("work mob"),
+            (5, vec!["\"Mobile App\" ".to_string()])
+        );
+        // An open quote is part of the word being completed.
+        assert_eq!(
+            complete("work \"Mob"),
+            (5, vec!["\"Mobile App\" ".to_string()])
+        );
+        assert_eq!(
+            complete("work "),
+            (5, vec!["\"Mobile App\" ".to_string(), "api ".to_string()])
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::display::format_clock;
+use chrono::Utc;
+use netupi_core::hooks::timer_name;
+use netupi_core::{ActiveTimer, TimerConfig, TimerState, TimerType};
+use serde_json::json;
+
+/// Output of `status --format`, one line per call.
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum StatusFormat {
+    /// JSON for a waybar custom module with `"return-type": "json"`
+    Waybar,
+    /// Text for a polybar script module; paused timers are dimmed
+    Polybar,
+    /// Text for tmux's status-right; paused timers are dimmed
+    Tmux,
+    /// Every detail as JSON
+    Json,
+    /// The `display.status_template` setting, or `--template`
+    Template,
+}
+
+/// The running timer, as shown in a status bar.
+struct StatusLine {
+    timer: &'static str,
+    state: &'static str,
+    icon: &'static str,
+    elapsed: chrono::Duration,
+    remaining: Option<chrono::Duration>,
+    target: Option<chrono::Duration>,
+    project: String,
+    description: String,
+}
+
+impl StatusLine {
+    fn new(active: Option<&ActiveTimer>) -> Option<Self> {
+        let active = active?;
+        let timer = &active.timer;
+        let now = Utc::now();
+        let icon = match (timer.state, timer.timer_type) {
+            (TimerState::Paused, _) => "⏸️",
+            (_, TimerType::PomodoroWork) => "🍅",
+            (_, TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak) => "☕",
+            (_, TimerType::Stopwatch) => "⏱️",
+            (_, TimerType::CustomTimer) => "⏳",
+        };
+        let session = active.session.as_ref();
+        Some(Self {
+            timer: timer_name(timer.timer_type),
+            state: match timer.state {
+                TimerState::Paused => "paused",
+                _ => "running",
+            },
+            icon,
+            // Whole seconds, so a stopwatch doesn't show 00:01 after half a second.
+            elapsed: chrono::Duration::seconds(timer.elapsed_at(now).num_seconds()),
+            remaining: timer.remaining_at(now),
+            target: timer.target_duration,
+            project: session
+                .and_then(|s| s.tags.first().cloned())
+                .unwrap_or_default(),
+            description: session
+                .and_then(|s| s.description.clone())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Time left for countdowns, time so far otherwise.
+    fn clock(&self) -> String {
+        format_clock(self.remaining.unwrap_or(self.elapsed))
+    }
+
+    /// The value of a `{placeholder}`, if `name` is one.
+    fn field(&self, name: &str) -> Option<String> {
+        Some(match name {
+            "icon" => self.icon.to_string(),
+            "clock" => self.clock(),
+            "elapsed" => format_clock(self.elapsed),
+            "remaining" => self.remaining.map(format_clock).unwrap_or_default(),
+            "timer" => self.timer.to_string(),
+            "state" => self.state.to_string(),
+            "project" => self.project.clone(),
+            "description" => self.description.clone(),
+            _ => return None,
+        })
+    }
+
+    /// Fill in the template's placeholders in one pass, so braces in a
+    /// project name or description are left as they are. Values go through
+    /// `escape`; the template itself may use the bar's formatting codes.
+    fn render(&self, template: &str, escape: fn(&str) -> String) -> String {
+        let mut text = String::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            text.push_str(&rest[..open]);
+            rest = &rest[open..];
+            let value = rest
+                .find('}')
+                .and_then(|close| Some((self.field(&rest[1..close])?, close)));
+            match value {
+                Some((value, close)) => {
+                    text.push_str(&escape(&value));
+                    rest = &rest[close + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text.push_str(rest);
+        text.trim().to_string()
+    }
+
+    /// How far a countdown has got, 0 to 100.
+    fn percentage(&self) -> Option<i64> {
+        let target = self.target?.num_seconds();
+        (target > 0).then(|| (self.elapsed.num_seconds() * 100 / target).clamp(0, 100))
+    }
+}
+
+/// The line a status bar shows for `active`; idle timers give an empty
+/// line (or idle JSON), which most bars hide.
+pub fn status_line(
+    active: Option<&ActiveTimer>,
+    format: StatusFormat,
+    template: Option<&str>,
+    config: &TimerConfig,
+) -> String {
+    let line = StatusLine::new(active);
+    let template = template.unwrap_or(&config.display.status_template);
+    // Polybar reads %{...} and tmux #[...] as formatting, even in a
+    // project name.
+    let escape: fn(&str) -> String = match format {
+        StatusFormat::Polybar => |value| value.replace('%', "%%"),
+        StatusFormat::Tmux => |value| value.replace('#', "##"),
+        _ => str::to_string,
+    };
+    let text = line
+        .as_ref()
+        .map(|line| line.render(template, escape))
+        .unwrap_or_default();
+    let paused = line.as_ref().is_some_and(|line| line.state == "paused");
+
+    match format {
+        StatusFormat::Template => text,
+        StatusFormat::Polybar if paused => format!("%{{F#707880}}{}%{{F-}}", text),
+        StatusFormat::Tmux if paused => format!("#[dim]{}#[default]", text),
+        StatusFormat::Polybar | StatusFormat::Tmux => text,
+        StatusFormat::Waybar => match &line {
+            Some(line) => {
+                let mut tooltip = format!("{} {}", line.timer.replace('_', " "), line.state);
+                if !line.project.is_empty() {
+                    tooltip.push_str(&format!(" on {}", line.project));
+                }
+                json!({
+                    "text": text,
+                    "alt": line.timer,
+                    "class": line.state,
+                    "tooltip": tooltip,
+                    "percentage": line.percentage().unwrap_or(0),
+                })
+            }
+            None => json!({
+                "text": "",
+                "alt": "idle",
+                "class": "idle",
+                "tooltip": "No timer running",
+            }),
+        }
+        .to_string(),
+        StatusFormat::Json => match &line {
+            Some(line) => json!({
+                "state": line.state,
+                "timer": line.timer,
+                "project": (!line.project.is_empty()).then_some(&line.project),
+                "description": (!line.description.is_empty()).then_some(&line.description),
+                "elapsed_seconds": line.elapsed.num_seconds(),
+                "remaining_seconds": line.remaining.map(|r| r.num_seconds()),
+                "text": text,
+            }),
+            None => json!({"state": "idle"}),
+        }
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use netupi_core::{TimerData, WorkSession};
+    use serde_json::Value;
+
+    /// A Pomodoro on `project` with 12:34 left, paused or running.
+    fn pomodoro(project: &str, state: TimerState) -> ActiveTimer {
+        let elapsed = chrono::Duration::seconds(12 * 60 + 26);
+        let now = Utc::now();
+        ActiveTimer {
+            pid: std::process::id(),
+            timer: TimerData {
+                timer_type: TimerType::PomodoroWork,
+                state,
+                target_duration: Some(chrono::Duration::minutes(25)),
+                elapsed,
+                paused_time: chrono::Duration::zero(),
+                start_time: Some(now - elapsed),
+                pause_start: (state == TimerState::Paused).then_some(now),
+            },
+            session: Some(WorkSession {
+                id: Default::default(),
+                session_type: TimerType::PomodoroWork,
+                start_time: now - elapsed,
+                end_time: None,
+                duration: chrono::Duration::zero(),
+                paused_duration: chrono::Duration::zero(),
+                description: Some("auth".to_string()),
+                tags: vec![project.to_string()],
+                billable: None,
+                hourly_rate: None,
+                invoice: None,
+                pause_count: 0,
+                target_duration: Some(chrono::Duration::minutes(25)),
+            }),
+            cycle_preset: None,
+        }
+    }
+
+    fn line(active: Option<&ActiveTimer>, format: StatusFormat) -> String {
+        status_line(active, format, None, &TimerConfig::default())
+    }
+
+    fn json(active: Option<&ActiveTimer>, format: StatusFormat) -> Value {
+        serde_json::from_str(&line(active, format)).unwrap()
+    }
+
+    #[test]
+    fn test_idle_status_line() {
+        for format in [
+            StatusFormat::Template,
+            StatusFormat::Polybar,
+            StatusFormat::Tmux,
+        ] {
+            assert_eq!(line(None, format), "");
+        }
+        assert_eq!(
+            json(None, StatusFormat::Waybar),
+            serde_json::json!({
+                "text": "",
+                "alt": "idle",
+                "class": "idle",
+                "tooltip": "No timer running",
+            })
+        );
+        assert_eq!(
+            json(None, StatusFormat::Json),
+            serde_json::json!({"state": "idle"})
+        );
+    }
+
+    #[test]
+    fn test_running_and_paused_status_lines() {
+        let running = pomodoro("api", TimerState::Running);
+        for format in [
+            StatusFormat::Template,
+            StatusFormat::Polybar,
+            StatusFormat::Tmux,
+        ] {
+            assert_eq!(line(Some(&running), format), "🍅 12:34 api");
+        }
+
+        let paused = pomodoro("api", TimerState::Paused);
+        assert_eq!(
+            line(Some(&paused), StatusFormat::Polybar),
+            "%{F#707880}⏸️ 12:34 api%{F-}"
+        );
+        assert_eq!(
+            line(Some(&paused), StatusFormat::Tmux),
+            "#[dim]⏸️ 12:34 api#[default]"
+        );
+        assert_eq!(
+            status_line(
+                Some(&paused),
+                StatusFormat::Template,
+                Some("{timer} {state} {elapsed} {remaining} {description} {unknown}"),
+                &TimerConfig::default(),
+            ),
+            "pomodoro paused 12:26 12:34 auth {unknown}"
+        );
+    }
+
+    #[test]
+    fn test_waybar_and_json_status_lines() {
+        let paused = pomodoro("api", TimerState::Paused);
+        assert_eq!(
+            json(Some(&paused), StatusFormat::Waybar),
+            serde_json::json!({
+                "text": "⏸️ 12:34 api",
+                "alt": "pomodoro",
+                "class": "paused",
+                "tooltip": "pomodoro paused on api",
+                "percentage": 49,
+            })
+        );
+        assert_eq!(
+            json(Some(&paused), StatusFormat::Json),
+            serde_json::json!({
+                "state": "paused",
+                "timer": "pomodoro",
+                "project": "api",
+                "description": "auth",
+                "elapsed_seconds": 746,
+                "remaining_seconds": 754,
+                "text": "⏸️ 12:34 api",
+            })
+        );
+    }
+
+    #[test]
+    fn test_project_names_are_escaped_for_the_bar() {
+        let click = pomodoro("%{A:reboot:}x%{A}", TimerState::Running);
+        assert_eq!(
+            line(Some(&click), StatusFormat::Polybar),
+            "🍅 12:34 %%{A:reboot:}x%%{A}"
+        );
+        let style = pomodoro("#[fg=red]x", TimerState::Running);
+        assert_eq!(
+            line(Some(&style), StatusFormat::Tmux),
+            "🍅 12:34 ##[fg=red]x"
+        );
+        let placeholder = pomodoro("{description}", TimerState::Running);
+        assert_eq!(
+            line(Some(&placeholder), StatusFormat::Template),
+            "🍅 12:34 {description}"
+        );
+    }
+}
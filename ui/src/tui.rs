@@ -0,0 +1,543 @@
+use crate::display::{
+    format_clock, format_short_duration, format_summary_time, notification_message, truncate,
+};
+use chrono::{Duration, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Stylize;
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+use netupi_core::calendar::Calendar;
+use netupi_core::config::TimerPreset;
+use netupi_core::hooks::HookRunner;
+use netupi_core::notify::{self, Notification, NotificationSink, Notifier};
+use netupi_core::{
+    NetupiCore, PersistenceError, ProjectSummary, StartRequest, TimerEvent, TimerState,
+    TimerStatus, TimerType, WorkSession,
+};
+use std::io::{self, Write};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+const KEYS: &str = "w pomodoro  t track  b break  l long break  p pause/resume  x stop  \
+                    ←/→ project  s switch  q quit";
+
+/// Sessions listed under "Recent".
+const RECENT: usize = 8;
+
+/// Digits 0-9 and ':' for the big clock, five rows of three cells each.
+const GLYPHS: [[&str; 5]; 11] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    ["  █", "  █", "  █", "  █", "  █"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+    [" ", "█", " ", "█", " "],
+];
+
+/// Run the dashboard until `q`, then stop and save the running session.
+pub async fn run(core: NetupiCore) -> Result<(), PersistenceError> {
+    let (messages_tx, mut messages) = mpsc::unbounded_channel();
+    let mut sinks = notify::default_sinks();
+    sinks.push(Arc::new(MessageSink(messages_tx)));
+    let notifier = Notifier::spawn(&core, sinks);
+    let hooks = HookRunner::spawn(&core);
+    let mut events = core.timer().subscribe_events();
+    let mut keys = spawn_key_reader();
+
+    let mut dashboard = Dashboard::load(&core).await?;
+    let screen = Screen::enter()?;
+    let mut redraw = tokio::time::interval(std::time::Duration::from_millis(250));
+    loop {
+        tokio::select! {
+            Some(key) = keys.recv() => {
+                match dashboard.handle_key(&core, key).await {
+                    Ok(true) => break,
+                    Ok(false) => {}
+                    Err(e) => dashboard.message = Some(format!("❌ Error: {}", e)),
+                }
+            }
+            Some(event) = events.recv() => {
+                if !matches!(event, TimerEvent::Tick(_))
+                    && let Err(e) = dashboard.refresh(&core).await
+                {
+                    dashboard.message = Some(format!("❌ Error: {}", e));
+                }
+            }
+            Some(message) = messages.recv() => dashboard.message = Some(message),
+            _ = redraw.tick() => {}
+        }
+        dashboard.draw(&mut io::stdout())?;
+    }
+    drop(screen);
+
+    let stopped = core.stop().await?;
+    hooks.finish().await;
+    notifier.finish().await;
+    if stopped.is_some() {
+        println!("⏹️  The running session was saved.");
+    }
+    Ok(())
+}
+
+/// Raw mode on the alternate screen, restored when dropped.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Key presses, read on a thread of their own since crossterm blocks.
+fn spawn_key_reader() -> mpsc::UnboundedReceiver<KeyEvent> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !tx.is_closed() {
+            match event::poll(std::time::Duration::from_millis(100)) {
+                Ok(false) => continue,
+                Ok(true) => {}
+                Err(_) => break,
+            }
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    if tx.send(key).is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+/// Shows notifications in the dashboard's message line.
+struct MessageSink(mpsc::UnboundedSender<String>);
+
+impl NotificationSink for MessageSink {
+    fn notify(&self, notification: &Notification) {
+        let _ = self.0.send(notification_message(notification));
+    }
+}
+
+struct Dashboard {
+    status: TimerStatus,
+    /// Timer lengths and cycle of the active preset.
+    rhythm: TimerPreset,
+    calendar: Calendar,
+    today: Vec<ProjectSummary>,
+    /// Newest first.
+    recent: Vec<WorkSession>,
+    /// Pomodoros completed in the current cycle.
+    cycle: u32,
+    projects: Vec<String>,
+    /// Project new work sessions are tracked against; `None` for none.
+    selected: Option<String>,
+    message: Option<String>,
+}
+
+impl Dashboard {
+    async fn load(core: &NetupiCore) -> Result<Self, PersistenceError> {
+        let mut dashboard = Self {
+            status: core.status().await?,
+            rhythm: core.timer().rhythm().await,
+            calendar: core.calendar().await?,
+            today: Vec::new(),
+            recent: Vec::new(),
+            cycle: 0,
+            projects: Vec::new(),
+            selected: core.timer().cycle_project().await,
+            message: None,
+        };
+        dashboard.refresh(core).await?;
+        if dashboard.selected.is_none() {
+            dashboard.selected = dashboard.projects.first().cloned();
+        }
+        Ok(dashboard)
+    }
+
+    async fn refresh(&mut self, core: &NetupiCore) -> Result<(), PersistenceError> {
+        self.status = core.status().await?;
+        self.rhythm = core.timer().rhythm().await;
+        self.calendar = core.calendar().await?;
+        self.today = core.get_today_project_summaries().await?;
+        self.today.sort_by_key(|s| std::cmp::Reverse(s.total));
+        let mut sessions = core.get_sessions().await?;
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.start_time));
+        sessions.truncate(RECENT);
+        self.recent = sessions;
+        self.cycle = core.cycle_position().await?;
+        self.projects = core
+            .get_projects()
+            .await?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        if let Some(project) = self.running_project()
+            && !self.projects.contains(&project)
+        {
+            self.projects.push(project);
+            self.projects.sort();
+        }
+        Ok(())
+    }
+
+    /// Whether a timer is running or paused.
+    fn timing(&self) -> bool {
+        matches!(
+            self.status.timer.state,
+            TimerState::Running | TimerState::Paused
+        )
+    }
+
+    fn running_project(&self) -> Option<String> {
+        self.status.session.as_ref()?.project().map(str::to_string)
+    }
+
+    /// Act on a key. Returns whether to quit.
+    async fn handle_key(
+        &mut self,
+        core: &NetupiCore,
+        key: KeyEvent,
+    ) -> Result<bool, PersistenceError> {
+        let ctrl_c =
+            key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+        let message = match key.code {
+            _ if ctrl_c => return Ok(true),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+            KeyCode::Char('w') => {
+                core.start(StartRequest::Pomodoro {
+                    project: self.selected.clone(),
+                    description: None,
+                    preset: None,
+                })
+                .await?;
+                "🍅 Pomodoro started".to_string()
+            }
+            KeyCode::Char('t') => {
+                core.start(self.track_request()).await?;
+                "⏱️  Tracking time".to_string()
+            }
+            KeyCode::Char('b') => {
+                core.start(StartRequest::ShortBreak).await?;
+                "☕ Short break started".to_string()
+            }
+            KeyCode::Char('l') => {
+                core.start(StartRequest::LongBreak).await?;
+                "🌴 Long break started".to_string()
+            }
+            KeyCode::Char('p') | KeyCode::Char(' ') => match self.status.timer.state {
+                TimerState::Running => {
+                    core.timer().pause_timer().await;
+                    "⏸️  Paused".to_string()
+                }
+                TimerState::Paused => {
+                    core.timer().resume_timer().await;
+                    "▶️  Resumed".to_string()
+                }
+                _ => "No timer is running".to_string(),
+            },
+            KeyCode::Char('x') => match core.stop().await? {
+                Some(_) => "⏹️  Stopped and saved".to_string(),
+                None => "No timer is running".to_string(),
+            },
+            KeyCode::Right | KeyCode::Tab => {
+                self.select(1);
+                return Ok(false);
+            }
+            KeyCode::Left | KeyCode::BackTab => {
+                self.select(-1);
+                return Ok(false);
+            }
+            KeyCode::Char('s') => self.switch(core).await?,
+            _ => return Ok(false),
+        };
+        self.message = Some(message);
+        self.refresh(core).await?;
+        Ok(false)
+    }
+
+    /// Step through the projects, with "no project" between the last and
+    /// the first.
+    fn select(&mut self, step: isize) {
+        let slots = self.projects.len() as isize + 1;
+        let current = self
+            .selected
+            .as_ref()
+            .and_then(|name| self.projects.iter().position(|p| p == name))
+            .map_or(slots - 1, |i| i as isize);
+        let next = (current + step).rem_euclid(slots) as usize;
+        self.selected = self.projects.get(next).cloned();
+    }
+
+    fn track_request(&self) -> StartRequest {
+        match &self.selected {
+            Some(project) => StartRequest::Project {
+                project: project.clone(),
+                description: None,
+                billable: None,
+                rate: None,
+            },
+            None => StartRequest::Stopwatch,
+        }
+    }
+
+    /// Save the running work session and carry on with the same kind of
+    /// timer on the selected project.
+    async fn switch(&mut self, core: &NetupiCore) -> Result<String, PersistenceError> {
+        if self.running_project() == self.selected && self.timing() {
+            return Ok("Already on this project".to_string());
+        }
+        let request = match self.status.timer.timer_type {
+            _ if !self.timing() => {
+                return Ok("No timer is running; w or t starts one".to_string());
+            }
+            TimerType::PomodoroWork => StartRequest::Pomodoro {
+                project: self.selected.clone(),
+                description: None,
+                preset: self.status.cycle_preset.clone(),
+            },
+            TimerType::Stopwatch => self.track_request(),
+            _ => return Ok("Breaks have no project".to_string()),
+        };
+        core.start(request).await?;
+        Ok(format!(
+            "🔀 Switched to {}",
+            self.selected.as_deref().unwrap_or("no project")
+        ))
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let lines = self.lines(width as usize);
+        for (row, line) in lines.iter().take(height as usize).enumerate() {
+            queue!(
+                out,
+                cursor::MoveTo(0, row as u16),
+                terminal::Clear(ClearType::CurrentLine),
+                crossterm::style::Print(line)
+            )?;
+        }
+        queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+        out.flush()
+    }
+
+    fn lines(&self, width: usize) -> Vec<String> {
+        let timer = &self.status.timer;
+        let now = Utc::now();
+        let idle = !self.timing();
+        let today = self.calendar.local(now).format("%A %-d %B");
+        let mut lines = vec![
+            format!(
+                " {}{}",
+                "Netupi23".bold(),
+                pad_left(&today.to_string(), width.saturating_sub(10))
+            ),
+            String::new(),
+        ];
+
+        // Time left for countdowns, whole seconds so far otherwise; the
+        // length of the next Pomodoro when idle.
+        let clock = if idle {
+            format_clock(self.rhythm.work)
+        } else {
+            timer
+                .remaining_at(now)
+                .map(format_clock)
+                .unwrap_or_else(|| {
+                    format_clock(Duration::seconds(timer.elapsed_at(now).num_seconds()))
+                })
+        };
+        let color = match (timer.state, timer.timer_type) {
+            _ if idle || timer.state == TimerState::Paused => crossterm::style::Color::DarkGrey,
+            (_, TimerType::PomodoroWork) => crossterm::style::Color::Red,
+            (_, TimerType::PomodoroShortBreak | TimerType::PomodoroLongBreak) => {
+                crossterm::style::Color::Green
+            }
+            (_, TimerType::Stopwatch | TimerType::CustomTimer) => crossterm::style::Color::Cyan,
+        };
+        for row in big_clock(&clock, width) {
+            lines.push(center(&row, width).with(color).to_string());
+        }
+        lines.push(String::new());
+
+        let summary = if idle {
+            "idle · w starts a Pomodoro, t tracks time".to_string()
+        } else {
+            let mut summary = format!(
+                "{} · {}",
+                timer_label(timer.timer_type),
+                if timer.state == TimerState::Paused {
+                    "paused"
+                } else {
+                    "running"
+                }
+            );
+            if let Some(session) = &self.status.session {
+                summary.push_str(&format!(" · {}", session.project().unwrap_or("no project")));
+                if let Some(description) = &session.description {
+                    summary.push_str(&format!(" · {}", description));
+                }
+            }
+            summary
+        };
+        lines.push(center(&summary, width).bold().to_string());
+        lines.push(center(&self.cycle_line(), width));
+        if let Some(budget) = &self.status.budget {
+            lines.push(center(
+                &format!("Budget: {}", crate::display::format_budget_status(budget)),
+                width,
+            ));
+        }
+        lines.push(String::new());
+
+        let column = (width / 2).max(30);
+        let left = self.today_lines(column);
+        let right = self.recent_lines();
+        lines.push(format!(
+            " {}{}",
+            format!("{:<1$}", "Today", column - 1).bold(),
+            "Recent sessions".bold()
+        ));
+        for i in 0..left.len().max(right.len()) {
+            let left = left.get(i).map(String::as_str).unwrap_or("");
+            let right = right.get(i).map(String::as_str).unwrap_or("");
+            lines.push(format!(" {}{}", pad_right(left, column - 1), right));
+        }
+        lines.push(String::new());
+
+        lines.push(format!(
+            " Project: ◀ {} ▶",
+            self.selected.as_deref().unwrap_or("no project").bold()
+        ));
+        lines.push(format!(" {}", self.message.as_deref().unwrap_or("")));
+        lines.push(format!(" {}", KEYS).dark_grey().to_string());
+        lines
+    }
+
+    /// Pomodoros done in the cycle as dots, e.g. "Cycle ● ● ◐ ○  2/4".
+    fn cycle_line(&self) -> String {
+        let interval = self.rhythm.long_break_interval.max(1);
+        let working = self.status.timer.timer_type == TimerType::PomodoroWork && self.timing();
+        let dots: Vec<&str> = (0..interval)
+            .map(|i| match i {
+                _ if i < self.cycle => "●",
+                _ if i == self.cycle && working => "◐",
+                _ => "○",
+            })
+            .collect();
+        let mut line = format!("Cycle {}  {}/{}", dots.join(" "), self.cycle, interval);
+        if self.cycle >= interval {
+            line.push_str(" · long break due");
+        }
+        if let Some(preset) = &self.status.cycle_preset {
+            line.push_str(&format!(" · {}", preset));
+        }
+        line
+    }
+
+    fn today_lines(&self, column: usize) -> Vec<String> {
+        if self.today.is_empty() {
+            return vec!["Nothing tracked yet".to_string()];
+        }
+        let name_width = column.saturating_sub(20).clamp(8, 24);
+        self.today
+            .iter()
+            .map(|summary| {
+                format!(
+                    "{} {}",
+                    pad_right(&truncate(&summary.name, name_width), name_width),
+                    format_summary_time(summary)
+                )
+            })
+            .collect()
+    }
+
+    fn recent_lines(&self) -> Vec<String> {
+        if self.recent.is_empty() {
+            return vec!["No sessions yet".to_string()];
+        }
+        self.recent
+            .iter()
+            .map(|session| {
+                format!(
+                    "{}  {:<10} {:<14} {}",
+                    self.calendar.local(session.start_time).format("%a %H:%M"),
+                    timer_label(session.session_type),
+                    truncate(session.project().unwrap_or("-"), 14),
+                    format_short_duration(session.duration)
+                )
+            })
+            .collect()
+    }
+}
+
+fn timer_label(timer_type: TimerType) -> &'static str {
+    match timer_type {
+        TimerType::PomodoroWork => "Pomodoro",
+        TimerType::PomodoroShortBreak => "Break",
+        TimerType::PomodoroLongBreak => "Long break",
+        TimerType::Stopwatch => "Stopwatch",
+        TimerType::CustomTimer => "Countdown",
+    }
+}
+
+/// `clock` in block digits, or as it is if the terminal is too narrow.
+fn big_clock(clock: &str, width: usize) -> Vec<String> {
+    let glyphs: Vec<&[&str; 5]> = clock
+        .chars()
+        .filter_map(|c| match c {
+            ':' => Some(&GLYPHS[10]),
+            _ => c.to_digit(10).map(|d| &GLYPHS[d as usize]),
+        })
+        .collect();
+    let rows: Vec<String> = (0..5)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[row].replace('█', "██").replace(' ', "  "))
+                .collect::<Vec<_>>()
+                .join("  ")
+        })
+        .collect();
+    if rows[0].chars().count() + 2 > width {
+        return vec![clock.to_string()];
+    }
+    rows
+}
+
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.chars().count()) / 2;
+    format!("{}{}", " ".repeat(padding), text)
+}
+
+fn pad_left(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        " ".repeat(width.saturating_sub(text.chars().count())),
+        text
+    )
+}
+
+fn pad_right(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(text.chars().count()))
+    )
+}